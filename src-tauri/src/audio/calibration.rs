@@ -0,0 +1,48 @@
+use super::vad::VadThresholds;
+
+/// Collects frame RMS values while the user stays quiet and turns them into thresholds.
+pub struct NoiseCalibrator {
+    frames_needed: usize,
+    levels: Vec<f32>,
+}
+
+impl NoiseCalibrator {
+    pub fn new(duration_ms: u64, frame_ms: u64) -> Self {
+        let frames_needed = (duration_ms / frame_ms).max(1) as usize;
+        Self { frames_needed, levels: Vec::with_capacity(frames_needed) }
+    }
+
+    /// Adds one frame's RMS. Returns the thresholds once enough audio has been sampled.
+    pub fn push(&mut self, rms: f32) -> Option<VadThresholds> {
+        self.levels.push(rms);
+        if self.levels.len() < self.frames_needed {
+            return None;
+        }
+        // Median, so a cough or a door slam during calibration doesn't skew the floor
+        self.levels.sort_by(|a, b| a.total_cmp(b));
+        let noise_floor = self.levels[self.levels.len() / 2];
+        Some(VadThresholds::from_noise_floor(noise_floor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floor_is_median_of_ambient_levels() {
+        // 300ms of 30ms frames
+        let mut calibrator = NoiseCalibrator::new(300, 30);
+        let levels = [0.002, 0.003, 0.002, 0.5, 0.0025, 0.002, 0.003, 0.0028, 0.002, 0.0021];
+        let mut result = None;
+        for (i, &level) in levels.iter().enumerate() {
+            result = calibrator.push(level);
+            assert_eq!(result.is_some(), i == levels.len() - 1);
+        }
+
+        let thresholds = result.unwrap();
+        assert_eq!(thresholds.noise_floor, 0.0025);
+        assert!((thresholds.start_threshold - 0.0075).abs() < 1e-6);
+        assert!((thresholds.stop_threshold - 0.005).abs() < 1e-6);
+    }
+}
@@ -1,93 +1,151 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
 
 use anyhow::{Context, Result};
 use log::{info, error};
-use ringbuf::HeapProducer;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::downmix::{ChannelMix, Downmixer};
+use super::ring::SampleSink;
+use super::source::AudioSource;
+
+/// What to open and how. Compared by the engine supervisor to detect when the stream
+/// has to be rebuilt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureConfig {
+    /// Device name, or `None` for the system default.
+    pub device: Option<String>,
+    pub channel_mix: ChannelMix,
+    /// Sample rate to request from the device if it supports it natively (skips resampling).
+    pub preferred_rate: Option<u32>,
+}
 
 pub struct AudioCapture {
     _stream: cpal::Stream,
+    sample_rate: u32,
+    failed: Arc<AtomicBool>,
 }
 
 impl AudioCapture {
-    pub fn init(mut producer: HeapProducer<f32>) -> Result<(Self, u32)> {
+    /// Returns the names of all input devices exposed by the default host.
+    pub fn list_input_devices() -> Result<Vec<String>> {
+        let host = cpal::default_host();
+        let devices = host.input_devices()
+            .context("Failed to enumerate input devices")?;
+        Ok(devices.filter_map(|d| d.name().ok()).collect())
+    }
+
+    /// Opens the configured input device, folding its channels to mono.
+    pub fn init(sink: SampleSink, capture_config: &CaptureConfig) -> Result<Self> {
         let host = cpal::default_host();
         
-        // 1. Get Default Input Device
-        let device = host.default_input_device()
-            .context("No input device found")?;
+        // 1. Get Input Device
+        let device = match capture_config.device.as_deref() {
+            Some(name) => host.input_devices()
+                .context("Failed to enumerate input devices")?
+                .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+                .with_context(|| format!("Input device '{}' not found", name))?,
+            None => host.default_input_device()
+                .context("No input device found")?,
+        };
         
         info!("Input device: {}", device.name().unwrap_or("Unknown".to_string()));
 
         // 2. Configure Stream
-        let config = device.default_input_config()
+        let default_config = device.default_input_config()
             .context("Failed to get default input config")?;
             
-        info!("Default config: Channels={}, SampleRate={}", config.channels(), config.sample_rate().0);
+        info!("Default config: Channels={}, SampleRate={}", default_config.channels(), default_config.sample_rate().0);
 
-        // We want to handle errors from the stream
-        let err_fn = |err| error!("an error occurred on stream: {}", err);
+        let config = match capture_config.preferred_rate.and_then(|rate| native_config(&device, &default_config, rate)) {
+            Some(native) => {
+                info!("Device supports {}Hz natively: Channels={}, Format={:?}", native.sample_rate().0, native.channels(), native.sample_format());
+                native
+            }
+            None => default_config,
+        };
+
+        // We want to handle errors from the stream. Any error marks the capture as failed
+        // so the engine supervisor can tear it down and reconnect.
+        let failed = Arc::new(AtomicBool::new(false));
+        let failed_clone = failed.clone();
+        let err_fn = move |err: cpal::StreamError| {
+            error!("an error occurred on stream: {}", err);
+            failed_clone.store(true, Ordering::Relaxed);
+        };
 
         // 3. Build Stream based on sample format
         let channels = config.channels() as usize;
         let sample_rate = config.sample_rate().0;
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => device.build_input_stream(
-                &config.into(),
-                move |data: &[f32], _: &_| write_f32(data, channels, &mut producer),
-                err_fn,
-                None,
-            )?,
-            cpal::SampleFormat::I16 => device.build_input_stream(
-                &config.into(),
-                move |data: &[i16], _: &_| write_i16(data, channels, &mut producer),
-                err_fn,
-                None,
-            )?,
-            cpal::SampleFormat::U16 => device.build_input_stream(
-                &config.into(),
-                move |data: &[u16], _: &_| write_u16(data, channels, &mut producer),
-                err_fn,
-                None,
-            )?,
+        let downmixer = Downmixer::new(channels, capture_config.channel_mix.clone(), sample_rate);
+        let sample_format = config.sample_format();
+        let stream_config: cpal::StreamConfig = config.into();
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &stream_config, downmixer, sink, err_fn)?,
+            cpal::SampleFormat::F64 => build_stream::<f64>(&device, &stream_config, downmixer, sink, err_fn)?,
+            cpal::SampleFormat::I8 => build_stream::<i8>(&device, &stream_config, downmixer, sink, err_fn)?,
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &stream_config, downmixer, sink, err_fn)?,
+            cpal::SampleFormat::I32 => build_stream::<i32>(&device, &stream_config, downmixer, sink, err_fn)?,
+            cpal::SampleFormat::U8 => build_stream::<u8>(&device, &stream_config, downmixer, sink, err_fn)?,
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &stream_config, downmixer, sink, err_fn)?,
             sample_format => anyhow::bail!("Unsupported sample format '{:?}'", sample_format),
         };
 
         stream.play()?;
-        Ok((AudioCapture { _stream: stream }, sample_rate))
+        Ok(AudioCapture { _stream: stream, sample_rate, failed })
     }
 }
 
-fn write_f32(input: &[f32], channels: usize, producer: &mut HeapProducer<f32>) {
-    for frame in input.chunks(channels) {
-        let sample = if channels == 2 {
-            (frame[0] + frame[1]) / 2.0
-        } else {
-            frame[0]
-        };
-        if producer.push(sample).is_err() {}
+impl AudioSource for AudioCapture {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
     }
-}
 
-fn write_i16(input: &[i16], channels: usize, producer: &mut HeapProducer<f32>) {
-    for frame in input.chunks(channels) {
-        let sample = if channels == 2 {
-            ((frame[0] as f32 / 32768.0) + (frame[1] as f32 / 32768.0)) / 2.0
-        } else {
-            frame[0] as f32 / 32768.0
-        };
-        if producer.push(sample).is_err() {}
+    /// True once the stream has reported an error (e.g. the device was unplugged).
+    fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
     }
 }
 
-fn write_u16(input: &[u16], channels: usize, producer: &mut HeapProducer<f32>) {
-    for frame in input.chunks(channels) {
-        let sample = if channels == 2 {
-             let s1 = (frame[0] as f32 - 32768.0) / 32768.0;
-             let s2 = (frame[1] as f32 - 32768.0) / 32768.0;
-             (s1 + s2) / 2.0
-        } else {
-            (frame[0] as f32 - 32768.0) / 32768.0
-        };
-        if producer.push(sample).is_err() {}
-    }
+/// Finds a supported config running at exactly `rate`, preferring the default
+/// sample format and channel count.
+fn native_config(device: &cpal::Device, default: &cpal::SupportedStreamConfig, rate: u32) -> Option<cpal::SupportedStreamConfig> {
+    let rate = cpal::SampleRate(rate);
+    device.supported_input_configs().ok()?
+        .filter(|range| range.min_sample_rate() <= rate && rate <= range.max_sample_rate())
+        .max_by_key(|range| (range.sample_format() == default.sample_format(), range.channels() == default.channels()))
+        .map(|range| range.with_sample_rate(rate))
+}
+
+/// Builds an input stream for sample type `T`, converting every sample to f32
+/// and folding each interleaved frame to mono before it enters the ring buffer.
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut downmixer: Downmixer,
+    mut sink: SampleSink,
+    err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels as usize;
+    let mut frame_f32 = vec![0.0f32; channels];
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &_| {
+            for frame in data.chunks(channels) {
+                for (dst, &src) in frame_f32.iter_mut().zip(frame) {
+                    *dst = f32::from_sample(src);
+                }
+                sink.push(downmixer.process_frame(&frame_f32[..frame.len()]));
+            }
+            sink.notify();
+        },
+        err_fn,
+        None,
+    )?;
+    Ok(stream)
 }
@@ -0,0 +1,160 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// How interleaved multi-channel input is folded down to mono.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ChannelMix {
+    /// Equal-weight average of every healthy channel.
+    #[default]
+    Average,
+    /// A single channel (0-based). Falls back to `Average` if it is missing or broken.
+    Single { channel: usize },
+    /// Weighted sum, normalized over the healthy channels. Missing weights count as 0.
+    Weighted { weights: Vec<f32> },
+}
+
+/// A channel that stays at exactly zero this long while another channel carries signal is
+/// treated as broken (dead capsule, unconnected input on an interface) and left out of the mix.
+const BROKEN_CHANNEL_SECS: f32 = 2.0;
+const SILENT_EPSILON: f32 = 1e-9;
+
+pub struct Downmixer {
+    channels: usize,
+    mix: ChannelMix,
+    broken_after_frames: usize,
+    zero_run: Vec<usize>,
+    excluded: Vec<bool>,
+}
+
+impl Downmixer {
+    pub fn new(channels: usize, mix: ChannelMix, sample_rate: u32) -> Self {
+        let channels = channels.max(1);
+        let mix = match mix {
+            ChannelMix::Single { channel } if channel >= channels => {
+                warn!("Channel {} requested but device has {} channels. Using average.", channel, channels);
+                ChannelMix::Average
+            }
+            other => other,
+        };
+
+        Self {
+            channels,
+            mix,
+            broken_after_frames: (sample_rate as f32 * BROKEN_CHANNEL_SECS) as usize,
+            zero_run: vec![0; channels],
+            excluded: vec![false; channels],
+        }
+    }
+
+    /// Folds one interleaved frame (one sample per channel) into a mono sample.
+    pub fn process_frame(&mut self, frame: &[f32]) -> f32 {
+        if self.channels == 1 {
+            return frame.first().copied().unwrap_or(0.0);
+        }
+        self.track_broken_channels(frame);
+
+        match &self.mix {
+            ChannelMix::Single { channel } if !self.excluded[*channel] => {
+                frame.get(*channel).copied().unwrap_or(0.0)
+            }
+            ChannelMix::Weighted { weights } => {
+                let mut sum = 0.0;
+                let mut weight_sum = 0.0;
+                for (i, &s) in frame.iter().enumerate() {
+                    if self.excluded[i] { continue; }
+                    let w = weights.get(i).copied().unwrap_or(0.0);
+                    sum += s * w;
+                    weight_sum += w;
+                }
+                if weight_sum > 0.0 { sum / weight_sum } else { self.average(frame) }
+            }
+            _ => self.average(frame),
+        }
+    }
+
+    fn average(&self, frame: &[f32]) -> f32 {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for (i, &s) in frame.iter().enumerate() {
+            if self.excluded[i] { continue; }
+            sum += s;
+            count += 1;
+        }
+        if count > 0 { sum / count as f32 } else { 0.0 }
+    }
+
+    fn track_broken_channels(&mut self, frame: &[f32]) {
+        for (i, &s) in frame.iter().enumerate().take(self.channels) {
+            if s.abs() <= SILENT_EPSILON {
+                self.zero_run[i] = self.zero_run[i].saturating_add(1);
+            } else {
+                self.zero_run[i] = 0;
+                if self.excluded[i] {
+                    self.excluded[i] = false;
+                    info!("🎚️ Channel {} carries signal again. Re-enabling.", i);
+                }
+            }
+        }
+
+        // Only exclude a dead channel while some other channel is alive;
+        // if everything is silent the room is just quiet (or the mic is muted).
+        let any_alive = self.zero_run.iter().any(|&run| run < self.broken_after_frames);
+        if !any_alive { return; }
+        for i in 0..self.channels {
+            if !self.excluded[i] && self.zero_run[i] >= self.broken_after_frames {
+                self.excluded[i] = true;
+                warn!("🎚️ Channel {} has been silent for {}s. Excluding it from the mix.", i, BROKEN_CHANNEL_SECS);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_uses_every_channel() {
+        let mut dm = Downmixer::new(4, ChannelMix::Average, 16000);
+        let out = dm.process_frame(&[0.4, 0.2, -0.2, 0.4]);
+        assert!((out - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_single_and_weighted() {
+        let mut single = Downmixer::new(4, ChannelMix::Single { channel: 2 }, 16000);
+        assert_eq!(single.process_frame(&[0.1, 0.2, 0.3, 0.4]), 0.3);
+
+        let mut out_of_range = Downmixer::new(2, ChannelMix::Single { channel: 5 }, 16000);
+        assert!((out_of_range.process_frame(&[0.2, 0.4]) - 0.3).abs() < 1e-6);
+
+        let mut weighted = Downmixer::new(3, ChannelMix::Weighted { weights: vec![3.0, 1.0] }, 16000);
+        // Third channel has no weight: (0.4*3 + 0.8*1) / 4
+        assert!((weighted.process_frame(&[0.4, 0.8, 1.0]) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dead_channel_is_excluded_and_restored() {
+        // 10 Hz "sample rate" so 2s of silence is 20 frames
+        let mut dm = Downmixer::new(2, ChannelMix::Average, 10);
+        for _ in 0..19 {
+            assert!((dm.process_frame(&[0.5, 0.0]) - 0.25).abs() < 1e-6);
+        }
+        // 20th silent frame trips the detector
+        assert_eq!(dm.process_frame(&[0.5, 0.0]), 0.5);
+        assert_eq!(dm.process_frame(&[0.5, 0.0]), 0.5);
+
+        // Signal on the dead channel brings it back
+        assert!((dm.process_frame(&[0.5, 0.1]) - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_all_silent_channels_are_kept() {
+        let mut dm = Downmixer::new(2, ChannelMix::Single { channel: 1 }, 10);
+        for _ in 0..50 {
+            dm.process_frame(&[0.0, 0.0]);
+        }
+        assert_eq!(dm.process_frame(&[0.2, 0.6]), 0.6);
+    }
+}
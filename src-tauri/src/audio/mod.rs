@@ -1,3 +1,11 @@
 pub mod vad;
+pub mod calibration;
 pub mod capture;
+pub mod downmix;
 pub mod resample;
+pub mod ring;
+pub mod segmenter;
+#[cfg(feature = "silero")]
+pub mod silero;
+pub mod source;
+pub mod spectral;
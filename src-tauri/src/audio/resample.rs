@@ -1,44 +1,227 @@
-use rubato::{Resampler, FastFixedIn, PolynomialDegree};
+use rubato::{
+    FastFixedIn, PolynomialDegree, Resampler, SincFixedIn, SincInterpolationParameters,
+    SincInterpolationType, VecResampler, WindowFunction,
+};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResampleQuality {
+    /// Cubic polynomial interpolation. Cheap, fine for speech.
+    #[default]
+    Fast,
+    /// Windowed sinc interpolation with proper anti-aliasing.
+    High,
+}
+
+/// Object-safe view of a rubato resampler that can also be reset
+/// (`VecResampler` alone does not expose `reset`).
+trait StreamResampler: VecResampler<f32> {
+    fn reset_state(&mut self);
+}
+
+impl<R: Resampler<f32>> StreamResampler for R {
+    fn reset_state(&mut self) {
+        Resampler::reset(self);
+    }
+}
+
+/// Streaming mono resampler.
+///
+/// Audio is fed frame by frame with `process` as it is captured, and `finish` drains the
+/// filter at the end of an utterance. Output is trimmed so that it lines up with the input:
+/// the filter delay is dropped from the start and the zero padding used to flush the last
+/// partial chunk is dropped from the end.
 pub struct AudioResampler {
-    resampler: FastFixedIn<f32>,
+    /// `None` when source and target rates match.
+    resampler: Option<Box<dyn StreamResampler>>,
+    target_rate: usize,
+    ratio: f64,
+    pending: Vec<f32>,
+    input_total: usize,
+    output_total: usize,
+    delay_to_skip: usize,
 }
 
 impl AudioResampler {
-    pub fn new(source_rate: usize, target_rate: usize, chunk_size: usize) -> Result<Self> {
-        let resampler = FastFixedIn::<f32>::new(
-            target_rate as f64 / source_rate as f64,
-            2.0, // max ratio
-            PolynomialDegree::Linear,
-            chunk_size,
-            1, // mono
-        )?;
-        
+    pub fn new(source_rate: usize, target_rate: usize, chunk_size: usize, quality: ResampleQuality) -> Result<Self> {
+        let ratio = target_rate as f64 / source_rate as f64;
+        let resampler: Option<Box<dyn StreamResampler>> = if source_rate == target_rate {
+            None
+        } else {
+            match quality {
+                ResampleQuality::Fast => Some(Box::new(FastFixedIn::<f32>::new(
+                    ratio,
+                    1.0, // fixed ratio
+                    PolynomialDegree::Cubic,
+                    chunk_size,
+                    1, // mono
+                )?)),
+                ResampleQuality::High => Some(Box::new(SincFixedIn::<f32>::new(
+                    ratio,
+                    1.0, // fixed ratio
+                    SincInterpolationParameters {
+                        sinc_len: 128,
+                        f_cutoff: 0.95,
+                        oversampling_factor: 128,
+                        interpolation: SincInterpolationType::Linear,
+                        window: WindowFunction::BlackmanHarris2,
+                    },
+                    chunk_size,
+                    1, // mono
+                )?)),
+            }
+        };
+
+        let delay_to_skip = resampler.as_ref().map(|r| r.output_delay()).unwrap_or(0);
         Ok(Self {
             resampler,
+            target_rate,
+            ratio,
+            pending: Vec::with_capacity(chunk_size),
+            input_total: 0,
+            output_total: 0,
+            delay_to_skip,
         })
     }
 
-    pub fn resample(&mut self, input: &[f32]) -> Result<Vec<f32>> {
-        let frames_required = self.resampler.input_frames_next();
-        let mut output = Vec::with_capacity((input.len() as f64 * 0.34) as usize); // Approx capacity
-        
-        // Process in chunks of the exact size rubato expects
-        for chunk in input.chunks(frames_required) {
-             let chunk_vec = if chunk.len() == frames_required {
-                 chunk.to_vec()
-             } else {
-                 // Pad potential last chunk with zeros
-                 let mut padded = chunk.to_vec();
-                 padded.resize(frames_required, 0.0);
-                 padded
-             };
-
-             let mut resampled_chunk = self.resampler.process(&[chunk_vec], None)?;
-             output.append(&mut resampled_chunk[0]);
+    pub fn target_rate(&self) -> usize {
+        self.target_rate
+    }
+
+    /// Feeds captured audio and returns whatever resampled output is ready.
+    pub fn process(&mut self, input: &[f32]) -> Result<Vec<f32>> {
+        self.input_total += input.len();
+        let Some(resampler) = self.resampler.as_mut() else {
+            self.output_total += input.len();
+            return Ok(input.to_vec());
+        };
+
+        self.pending.extend_from_slice(input);
+        let mut output = Vec::with_capacity((input.len() as f64 * self.ratio) as usize + 1);
+        loop {
+            let needed = resampler.input_frames_next();
+            if self.pending.len() < needed { break; }
+            let chunk: Vec<f32> = self.pending.drain(..needed).collect();
+            let mut resampled = resampler.process(&[chunk], None)?;
+            output.append(&mut resampled[0]);
+        }
+        Ok(self.emit(output))
+    }
+
+    /// Flushes the last partial chunk and the filter tail, then resets for the next utterance.
+    pub fn finish(&mut self) -> Result<Vec<f32>> {
+        let expected_total = (self.input_total as f64 * self.ratio).round() as usize;
+        let already_emitted = self.output_total;
+        let mut output = Vec::new();
+
+        if let Some(resampler) = self.resampler.as_mut() {
+            let pending = std::mem::take(&mut self.pending);
+            if !pending.is_empty() {
+                let mut resampled = resampler.process_partial(Some(&[pending]), None)?;
+                output.append(&mut resampled[0]);
+            }
+            // Push silence through until every real input sample has come out the other side
+            while already_emitted + output.len().saturating_sub(self.delay_to_skip) < expected_total {
+                let mut resampled = resampler.process_partial(None, None)?;
+                if resampled[0].is_empty() { break; }
+                output.append(&mut resampled[0]);
+            }
         }
-        
+
+        let mut output = self.emit(output);
+        output.truncate(expected_total.saturating_sub(already_emitted));
+        self.reset();
+        Ok(output)
+    }
+
+    /// Clears filter state and counters so the next utterance starts clean.
+    pub fn reset(&mut self) {
+        if let Some(resampler) = self.resampler.as_mut() {
+            resampler.reset_state();
+            self.delay_to_skip = resampler.output_delay();
+        }
+        self.pending.clear();
+        self.input_total = 0;
+        self.output_total = 0;
+    }
+
+    /// Resamples a complete buffer in one go.
+    pub fn resample(&mut self, input: &[f32]) -> Result<Vec<f32>> {
+        self.reset();
+        let mut output = self.process(input)?;
+        output.extend(self.finish()?);
         Ok(output)
     }
+
+    /// Drops the leading filter delay and tracks how much output has been handed out.
+    fn emit(&mut self, mut output: Vec<f32>) -> Vec<f32> {
+        if self.delay_to_skip > 0 {
+            let skip = self.delay_to_skip.min(output.len());
+            output.drain(..skip);
+            self.delay_to_skip -= skip;
+        }
+        self.output_total += output.len();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(rate: usize, secs: f32) -> Vec<f32> {
+        (0..(rate as f32 * secs) as usize)
+            .map(|i| (i as f32 * 440.0 * 2.0 * std::f32::consts::PI / rate as f32).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_streaming_output_length_matches_ratio() {
+        for quality in [ResampleQuality::Fast, ResampleQuality::High] {
+            let input = tone(48000, 1.013);
+            let mut resampler = AudioResampler::new(48000, 16000, 1440, quality).unwrap();
+
+            let mut output = Vec::new();
+            for frame in input.chunks(1000) {
+                output.extend(resampler.process(frame).unwrap());
+            }
+            output.extend(resampler.finish().unwrap());
+
+            let expected = (input.len() as f64 / 3.0).round() as usize;
+            assert_eq!(output.len(), expected, "{:?}", quality);
+        }
+    }
+
+    #[test]
+    fn test_tail_is_not_padded_with_silence() {
+        let input = tone(44100, 0.5);
+        let mut resampler = AudioResampler::new(44100, 16000, 1323, ResampleQuality::High).unwrap();
+        let output = resampler.resample(&input).unwrap();
+
+        // The last 10ms must still carry the tone rather than zero padding
+        let tail = &output[output.len() - 160..];
+        let rms = (tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32).sqrt();
+        assert!(rms > 0.2, "tail rms {}", rms);
+    }
+
+    #[test]
+    fn test_reset_between_utterances() {
+        let mut resampler = AudioResampler::new(48000, 16000, 1440, ResampleQuality::Fast).unwrap();
+        let first = resampler.resample(&tone(48000, 0.3)).unwrap();
+        let second = resampler.resample(&tone(48000, 0.3)).unwrap();
+        assert_eq!(first.len(), second.len());
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_passthrough_when_rates_match() {
+        let mut resampler = AudioResampler::new(16000, 16000, 480, ResampleQuality::High).unwrap();
+        let input = tone(16000, 0.1);
+        assert_eq!(resampler.process(&input).unwrap(), input);
+        assert!(resampler.finish().unwrap().is_empty());
+    }
 }
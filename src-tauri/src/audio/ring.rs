@@ -0,0 +1,128 @@
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use ringbuf::{HeapConsumer, HeapProducer, HeapRb};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Creates the ring buffer between an audio source and the engine loop.
+pub fn sample_ring(capacity: usize) -> (SampleSink, FrameReader) {
+    let (producer, consumer) = HeapRb::<f32>::new(capacity).split();
+    // A single pending wake-up is enough: the reader drains everything available.
+    let (notify, ready) = crossbeam_channel::bounded(1);
+    let dropped = Arc::new(AtomicU64::new(0));
+    (
+        SampleSink { producer, notify, dropped: dropped.clone() },
+        FrameReader { consumer, ready, dropped },
+    )
+}
+
+/// Producer side, owned by the audio callback or replay thread.
+pub struct SampleSink {
+    producer: HeapProducer<f32>,
+    notify: Sender<()>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl SampleSink {
+    /// Pushes one sample, counting it as dropped if the ring buffer is full.
+    pub fn push(&mut self, sample: f32) {
+        if self.producer.push(sample).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.producer.is_full()
+    }
+
+    /// Wakes the reader. Call once per batch of pushed samples.
+    pub fn notify(&self) {
+        let _ = self.notify.try_send(());
+    }
+}
+
+pub enum FrameStatus {
+    Ready,
+    Timeout,
+    /// The sink was dropped; no more samples will arrive.
+    Closed,
+}
+
+/// Consumer side, owned by the engine loop.
+pub struct FrameReader {
+    consumer: HeapConsumer<f32>,
+    ready: Receiver<()>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FrameReader {
+    /// Blocks until `frame.len()` samples are available and copies them into `frame`.
+    pub fn read_frame(&mut self, frame: &mut [f32], timeout: Duration) -> FrameStatus {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.consumer.len() >= frame.len() {
+                self.consumer.pop_slice(frame);
+                return FrameStatus::Ready;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.ready.recv_timeout(remaining) {
+                Ok(()) => continue,
+                Err(RecvTimeoutError::Timeout) => return FrameStatus::Timeout,
+                Err(RecvTimeoutError::Disconnected) => return FrameStatus::Closed,
+            }
+        }
+    }
+
+    /// Total samples lost to ring-buffer overruns since the ring was created.
+    pub fn dropped_samples(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_fixed_frames_and_counts_overruns() {
+        let (mut sink, mut reader) = sample_ring(8);
+        let mut frame = [0.0f32; 4];
+
+        for i in 0..3 {
+            sink.push(i as f32);
+        }
+        sink.notify();
+        assert!(matches!(reader.read_frame(&mut frame, Duration::from_millis(5)), FrameStatus::Timeout));
+
+        for i in 3..10 {
+            sink.push(i as f32);
+        }
+        sink.notify();
+        // Capacity 8: samples 8 and 9 were dropped
+        assert_eq!(reader.dropped_samples(), 2);
+        assert!(matches!(reader.read_frame(&mut frame, Duration::from_millis(5)), FrameStatus::Ready));
+        assert_eq!(frame, [0.0, 1.0, 2.0, 3.0]);
+        assert!(matches!(reader.read_frame(&mut frame, Duration::from_millis(5)), FrameStatus::Ready));
+        assert_eq!(frame, [4.0, 5.0, 6.0, 7.0]);
+
+        drop(sink);
+        assert!(matches!(reader.read_frame(&mut frame, Duration::from_millis(5)), FrameStatus::Closed));
+    }
+
+    #[test]
+    fn test_wakes_on_notify_from_another_thread() {
+        let (mut sink, mut reader) = sample_ring(64);
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            for _ in 0..16 {
+                sink.push(0.5);
+            }
+            sink.notify();
+            sink
+        });
+        let mut frame = [0.0f32; 16];
+        assert!(matches!(reader.read_frame(&mut frame, Duration::from_secs(5)), FrameStatus::Ready));
+        assert!(frame.iter().all(|&s| s == 0.5));
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,388 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use log::info;
+
+use super::resample::AudioResampler;
+use super::vad::{EnergyVad, VadBackend, VadState, VadThresholds, VoiceDetector};
+
+/// An over-long utterance is split at the quietest window of its last second.
+const SPLIT_SEARCH_MS: usize = 1000;
+const SPLIT_WINDOW_MS: usize = 20;
+
+/// What opens and closes an utterance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationMode {
+    /// The voice detector decides.
+    #[default]
+    Vad,
+    /// Capture while the hotkey is held, transcribe on release.
+    PushToTalk,
+    /// First hotkey press starts capture, the next one ends it.
+    Toggle,
+}
+
+/// Result of feeding one frame into the segmenter.
+pub struct FrameOutcome {
+    pub state: VadState,
+    pub rms: f32,
+    /// True when `state` differs from the state after the previous frame.
+    pub state_changed: bool,
+    /// A finished utterance at the target sample rate, ready for transcription.
+    pub utterance: Option<Vec<f32>>,
+}
+
+/// Turns a stream of audio frames into utterances using the VAD,
+/// prepending the pre-roll captured just before speech was detected.
+///
+/// Speech is resampled frame by frame while it is captured, so the end of an
+/// utterance only has to flush the resampler's tail.
+///
+/// With a maximum utterance length set, long monologues are emitted in pieces while the
+/// speaker keeps going. Each cut lands in the quietest spot of the last second and the audio
+/// after it stays buffered for the next piece, so nothing is lost or sent twice.
+pub struct SpeechSegmenter {
+    vad: Box<dyn VoiceDetector>,
+    resampler: AudioResampler,
+    frame_ms: u64,
+    pre_roll_frames: usize,
+    pre_roll_buffer: VecDeque<Vec<f32>>,
+    voice_buffer: Vec<f32>,
+    max_utterance_samples: Option<usize>,
+    activation: ActivationMode,
+    /// Hotkey state for the manual activation modes.
+    gate_open: bool,
+    /// Frames of silence still appended after speech ends.
+    post_roll_frames: usize,
+    tail_frames_left: usize,
+    /// An utterance is open (speaking, or in its post-roll tail).
+    capturing: bool,
+    last_state: VadState,
+}
+
+impl SpeechSegmenter {
+    pub fn new(vad: Box<dyn VoiceDetector>, resampler: AudioResampler, frame_ms: u64, pre_roll_ms: u64) -> Self {
+        let pre_roll_frames = (pre_roll_ms / frame_ms) as usize;
+        Self {
+            vad,
+            resampler,
+            frame_ms,
+            pre_roll_frames,
+            pre_roll_buffer: VecDeque::with_capacity(pre_roll_frames),
+            voice_buffer: Vec::new(),
+            max_utterance_samples: None,
+            activation: ActivationMode::Vad,
+            gate_open: false,
+            post_roll_frames: 0,
+            tail_frames_left: 0,
+            capturing: false,
+            last_state: VadState::Silence,
+        }
+    }
+
+    pub fn process(&mut self, frame: &[f32]) -> Result<FrameOutcome> {
+        let rms = EnergyVad::calculate_rms(frame);
+        // The detector keeps running in the manual modes so its noise tracking stays current
+        let detected = self.vad.process_frame(frame);
+        let state = match self.activation {
+            ActivationMode::Vad => detected,
+            ActivationMode::PushToTalk | ActivationMode::Toggle if self.gate_open => VadState::Speaking,
+            ActivationMode::PushToTalk | ActivationMode::Toggle => VadState::Silence,
+        };
+        let mut utterance = None;
+
+        match state {
+            VadState::Speaking => {
+                // Speech resuming during the post-roll tail continues the same utterance
+                if !self.capturing {
+                    info!("🗣️ Speech started! Prepending {}ms of audio", self.pre_roll_buffer.len() as u64 * self.frame_ms);
+                    self.capturing = true;
+                    self.resampler.reset();
+                    for chunk in self.pre_roll_buffer.drain(..) {
+                        self.voice_buffer.extend(self.resampler.process(&chunk)?);
+                    }
+                }
+                self.tail_frames_left = self.post_roll_frames;
+
+                self.voice_buffer.extend(self.resampler.process(frame)?);
+                if self.max_utterance_samples.is_some_and(|max| self.voice_buffer.len() >= max) {
+                    let cut = self.split_point();
+                    let rest = self.voice_buffer.split_off(cut);
+                    info!("✂️ Utterance reached the length limit. Splitting after {} samples", cut);
+                    utterance = Some(std::mem::replace(&mut self.voice_buffer, rest));
+                }
+            }
+            VadState::Silence if self.capturing && self.tail_frames_left > 0 => {
+                self.voice_buffer.extend(self.resampler.process(frame)?);
+                self.tail_frames_left -= 1;
+                if self.tail_frames_left == 0 {
+                    utterance = self.close_utterance()?;
+                }
+            }
+            VadState::Silence => {
+                if self.capturing {
+                    utterance = self.close_utterance()?;
+                }
+                if self.pre_roll_buffer.len() >= self.pre_roll_frames {
+                    self.pre_roll_buffer.pop_front();
+                }
+                if self.pre_roll_frames > 0 {
+                    self.pre_roll_buffer.push_back(frame.to_vec());
+                }
+            }
+        }
+
+        let state_changed = std::mem::discriminant(&state) != std::mem::discriminant(&self.last_state);
+        self.last_state = state;
+
+        Ok(FrameOutcome { state, rms, state_changed, utterance })
+    }
+
+    /// Flushes the resampler and hands back the finished utterance.
+    fn close_utterance(&mut self) -> Result<Option<Vec<f32>>> {
+        self.capturing = false;
+        self.tail_frames_left = 0;
+        self.voice_buffer.extend(self.resampler.finish()?);
+        if self.voice_buffer.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(std::mem::take(&mut self.voice_buffer)))
+        }
+    }
+
+    /// Selects the activation mode. In the manual modes `gate_open` (hotkey held or toggled on)
+    /// replaces the detector's decision; pre-roll, resampling and splitting work the same.
+    pub fn set_activation(&mut self, mode: ActivationMode, gate_open: bool) {
+        self.activation = mode;
+        self.gate_open = gate_open;
+    }
+
+    /// Caps utterance length (in ms of audio). `0` means unlimited.
+    pub fn set_max_utterance(&mut self, max_utterance_ms: u64) {
+        let rate = self.resampler.target_rate() as u64;
+        self.max_utterance_samples = (max_utterance_ms > 0).then(|| (rate * max_utterance_ms / 1000) as usize);
+    }
+
+    /// Finds the centre of the lowest-energy window in the last second of buffered speech.
+    fn split_point(&self) -> usize {
+        let rate = self.resampler.target_rate();
+        let window = (rate * SPLIT_WINDOW_MS / 1000).max(1);
+        let hop = (window / 2).max(1);
+        let len = self.voice_buffer.len();
+
+        let mut best = (f32::MAX, len);
+        let mut start = len.saturating_sub(rate * SPLIT_SEARCH_MS / 1000);
+        while start + window <= len {
+            let rms = EnergyVad::calculate_rms(&self.voice_buffer[start..start + window]);
+            if rms < best.0 {
+                best = (rms, start + window / 2);
+            }
+            start += hop;
+        }
+        best.1
+    }
+
+    pub fn update_stop_window(&mut self, stop_window_ms: u64) {
+        self.vad.update_stop_window(stop_window_ms, self.frame_ms);
+    }
+
+    pub fn update_start_window(&mut self, start_window_ms: u64) {
+        self.vad.update_start_window(start_window_ms, self.frame_ms);
+    }
+
+    /// Audio kept from before speech is detected.
+    pub fn set_pre_roll(&mut self, pre_roll_ms: u64) {
+        self.pre_roll_frames = (pre_roll_ms / self.frame_ms) as usize;
+        while self.pre_roll_buffer.len() > self.pre_roll_frames {
+            self.pre_roll_buffer.pop_front();
+        }
+    }
+
+    /// Audio kept after speech ends, so trailing consonants aren't clipped.
+    pub fn set_post_roll(&mut self, post_roll_ms: u64) {
+        self.post_roll_frames = (post_roll_ms / self.frame_ms) as usize;
+    }
+
+    /// The detector actually in use (the configured one may have failed to load).
+    pub fn backend(&self) -> VadBackend {
+        self.vad.backend()
+    }
+
+    pub fn thresholds(&self) -> Option<VadThresholds> {
+        self.vad.thresholds()
+    }
+
+    pub fn set_thresholds(&mut self, thresholds: VadThresholds) {
+        self.vad.set_thresholds(thresholds);
+    }
+
+    pub fn set_adaptive(&mut self, adaptive: bool) {
+        self.vad.set_adaptive(adaptive);
+    }
+
+    /// Speech captured so far in the open utterance, for interim transcription.
+    /// `None` between utterances.
+    pub fn pending_audio(&self) -> Option<&[f32]> {
+        (self.capturing && !self.voice_buffer.is_empty()).then_some(self.voice_buffer.as_slice())
+    }
+
+    /// Hands back any speech captured so far, e.g. before the audio source is torn down.
+    pub fn flush(&mut self) -> Result<Option<Vec<f32>>> {
+        self.pre_roll_buffer.clear();
+        if self.capturing {
+            return self.close_utterance();
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::resample::ResampleQuality;
+
+    #[test]
+    fn test_utterance_includes_pre_roll() {
+        // 10ms frames of 10 samples, 30ms start window, 50ms stop window, 20ms pre-roll
+        let vad = EnergyVad::new(0.5, 0.2, 30, 50, 10);
+        let resampler = AudioResampler::new(1000, 1000, 10, ResampleQuality::Fast).unwrap();
+        let mut seg = SpeechSegmenter::new(Box::new(vad), resampler, 10, 20);
+
+        let quiet = vec![0.0f32; 10];
+        let loud = vec![0.8f32; 10];
+
+        for _ in 0..5 {
+            assert!(seg.process(&quiet).unwrap().utterance.is_none());
+        }
+        // Third loud frame triggers speech
+        seg.process(&loud).unwrap();
+        seg.process(&loud).unwrap();
+        let outcome = seg.process(&loud).unwrap();
+        assert!(matches!(outcome.state, VadState::Speaking));
+        assert!(outcome.state_changed);
+
+        let mut utterance = None;
+        for _ in 0..5 {
+            let outcome = seg.process(&quiet).unwrap();
+            if outcome.utterance.is_some() {
+                utterance = outcome.utterance;
+            }
+        }
+
+        // 2 pre-roll frames + trigger frame + 4 quiet frames before the 5th closes the utterance
+        let utterance = utterance.expect("utterance should be emitted");
+        assert_eq!(utterance.len(), 7 * 10);
+        assert!(seg.flush().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_post_roll_tail_and_resume() {
+        let vad = EnergyVad::new(0.5, 0.2, 10, 20, 10);
+        let resampler = AudioResampler::new(1000, 1000, 10, ResampleQuality::Fast).unwrap();
+        let mut seg = SpeechSegmenter::new(Box::new(vad), resampler, 10, 0);
+        seg.set_post_roll(30);
+
+        let quiet = vec![0.0f32; 10];
+        let loud = vec![0.8f32; 10];
+        seg.process(&loud).unwrap();
+        // The second quiet frame ends speech (stop window) and starts a 3-frame tail
+        assert!(seg.process(&quiet).unwrap().utterance.is_none());
+        assert!(matches!(seg.process(&quiet).unwrap().state, VadState::Silence));
+        assert!(seg.process(&quiet).unwrap().utterance.is_none());
+        // Speech resumes inside the tail: same utterance
+        seg.process(&loud).unwrap();
+        for _ in 0..3 {
+            assert!(seg.process(&quiet).unwrap().utterance.is_none());
+        }
+        let utterance = seg.process(&quiet).unwrap().utterance.expect("tail should close the utterance");
+        // loud, quiet, 2 tail | loud, quiet, 3 tail
+        assert_eq!(utterance.len(), 9 * 10);
+    }
+
+    #[test]
+    fn test_push_to_talk_ignores_detector() {
+        let vad = EnergyVad::new(0.5, 0.2, 30, 50, 10);
+        let resampler = AudioResampler::new(1000, 1000, 10, ResampleQuality::Fast).unwrap();
+        let mut seg = SpeechSegmenter::new(Box::new(vad), resampler, 10, 20);
+        seg.set_activation(ActivationMode::PushToTalk, false);
+
+        // Loud audio with the key up never starts an utterance
+        let loud = vec![0.8f32; 10];
+        for _ in 0..20 {
+            let outcome = seg.process(&loud).unwrap();
+            assert!(matches!(outcome.state, VadState::Silence));
+            assert!(outcome.utterance.is_none());
+        }
+
+        // Quiet audio with the key held is captured, pre-roll included
+        let quiet = vec![0.01f32; 10];
+        seg.set_activation(ActivationMode::PushToTalk, true);
+        for _ in 0..3 {
+            assert!(matches!(seg.process(&quiet).unwrap().state, VadState::Speaking));
+        }
+        seg.set_activation(ActivationMode::PushToTalk, false);
+        let utterance = seg.process(&quiet).unwrap().utterance.expect("release should emit");
+        assert_eq!(utterance.len(), (2 + 3) * 10);
+    }
+
+    #[test]
+    fn test_long_utterance_is_split_at_quiet_point() {
+        let vad = EnergyVad::new(0.5, 0.2, 30, 50, 10);
+        let resampler = AudioResampler::new(1000, 1000, 10, ResampleQuality::Fast).unwrap();
+        let mut seg = SpeechSegmenter::new(Box::new(vad), resampler, 10, 0);
+        seg.set_max_utterance(1500);
+
+        // Each sample carries its index so the pieces can be stitched back together.
+        // A softer stretch (still above the stop threshold) sits at 1200..1230.
+        let level = |i: usize| if (1200..1230).contains(&i) { 0.3 } else { 0.8 };
+        let mut pieces = Vec::new();
+        let mut fed = 0;
+        for _ in 0..250 {
+            let frame: Vec<f32> = (fed..fed + 10).map(|i| level(i) + i as f32 * 1e-6).collect();
+            fed += 10;
+            if let Some(piece) = seg.process(&frame).unwrap().utterance {
+                pieces.push(piece);
+            }
+        }
+        pieces.extend(seg.flush().unwrap());
+
+        // The first 20 samples went in before the VAD triggered (no pre-roll).
+        // The cut lands in the soft stretch rather than at the 1.5s limit.
+        assert!(pieces.len() >= 2);
+        let cut = pieces[0].len() + 20;
+        assert!((1200..1230).contains(&cut), "cut at {}", cut);
+
+        // Everything after the trigger is kept exactly once
+        let joined: Vec<f32> = pieces.concat();
+        assert_eq!(joined.len(), fed - 20);
+        for (offset, &s) in joined.iter().enumerate() {
+            let i = offset + 20;
+            assert!((s - (level(i) + i as f32 * 1e-6)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_pending_audio_tracks_open_utterance() {
+        let vad = EnergyVad::new(0.5, 0.2, 30, 50, 10);
+        let resampler = AudioResampler::new(1000, 1000, 10, ResampleQuality::Fast).unwrap();
+        let mut seg = SpeechSegmenter::new(Box::new(vad), resampler, 10, 0);
+
+        let loud = vec![0.8f32; 10];
+        let quiet = vec![0.0f32; 10];
+        seg.process(&loud).unwrap();
+        assert!(seg.pending_audio().is_none());
+        for _ in 0..4 {
+            seg.process(&loud).unwrap();
+        }
+        let pending = seg.pending_audio().expect("speech should be pending").len();
+
+        // The final utterance carries everything that was reported as pending
+        let mut utterance = None;
+        for _ in 0..6 {
+            utterance = utterance.or(seg.process(&quiet).unwrap().utterance);
+        }
+        assert!(utterance.unwrap().len() >= pending);
+        assert!(seg.pending_audio().is_none());
+    }
+}
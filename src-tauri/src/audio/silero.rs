@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use log::info;
+use ndarray::{Array2, Array3, Ix3};
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use std::path::Path;
+
+use super::resample::{AudioResampler, ResampleQuality};
+use super::vad::{EnergyVad, VadBackend, VadState, VoiceDetector};
+
+/// Silero v5 runs on 512-sample windows at 16kHz, each prefixed with the
+/// last 64 samples of the previous window.
+const SILERO_RATE: u32 = 16000;
+const WINDOW_SAMPLES: usize = 512;
+const CONTEXT_SAMPLES: usize = 64;
+/// Recurrent state shape: [2, batch, 128].
+const STATE_SHAPE: (usize, usize, usize) = (2, 1, 128);
+
+/// Speech probability thresholds, as recommended by upstream.
+const START_PROBABILITY: f32 = 0.5;
+const STOP_PROBABILITY: f32 = 0.35;
+
+/// Silero neural VAD on ONNX Runtime (CPU, single thread).
+///
+/// Capture frames are resampled to 16kHz and cut into model windows. Each capture frame is
+/// scored with the highest probability of the windows it completed, and the score goes through
+/// the same start/stop hysteresis as the energy detector.
+pub struct SileroVad {
+    session: Session,
+    state: Array3<f32>,
+    context: Vec<f32>,
+    resampler: AudioResampler,
+    pending: Vec<f32>,
+    last_probability: f32,
+    hysteresis: EnergyVad,
+}
+
+impl SileroVad {
+    pub fn new(
+        model_path: &Path,
+        sample_rate: u32,
+        start_window_ms: u64,
+        stop_window_ms: u64,
+        frame_rate_ms: u64,
+    ) -> Result<Self> {
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .with_intra_threads(1)?
+            .commit_from_file(model_path)
+            .with_context(|| format!("Failed to load Silero VAD model from {:?}", model_path))?;
+        info!("🧠 Silero VAD loaded from {:?}", model_path);
+
+        let chunk_size = (sample_rate as u64 * frame_rate_ms / 1000) as usize;
+        Ok(Self {
+            session,
+            state: Array3::zeros(STATE_SHAPE),
+            context: vec![0.0; CONTEXT_SAMPLES],
+            resampler: AudioResampler::new(sample_rate as usize, SILERO_RATE as usize, chunk_size, ResampleQuality::Fast)?,
+            pending: Vec::with_capacity(WINDOW_SAMPLES * 2),
+            last_probability: 0.0,
+            hysteresis: EnergyVad::new(START_PROBABILITY, STOP_PROBABILITY, start_window_ms, stop_window_ms, frame_rate_ms),
+        })
+    }
+
+    /// Runs the model on one 512-sample window and returns the speech probability.
+    fn infer(&mut self, window: &[f32]) -> Result<f32> {
+        let mut input = Vec::with_capacity(CONTEXT_SAMPLES + WINDOW_SAMPLES);
+        input.extend_from_slice(&self.context);
+        input.extend_from_slice(window);
+        let input = Array2::from_shape_vec((1, input.len()), input)?;
+        let sr = ndarray::arr0(SILERO_RATE as i64);
+
+        let (probability, state) = {
+            let outputs = self.session.run(ort::inputs![
+                "input" => input.view(),
+                "state" => self.state.view(),
+                "sr" => sr.view(),
+            ]?)?;
+            let probability = outputs["output"].try_extract_tensor::<f32>()?
+                .iter()
+                .next()
+                .copied()
+                .unwrap_or(0.0);
+            let state = outputs["stateN"].try_extract_tensor::<f32>()?
+                .to_owned()
+                .into_dimensionality::<Ix3>()?;
+            (probability, state)
+        };
+
+        self.state = state;
+        self.context.copy_from_slice(&window[WINDOW_SAMPLES - CONTEXT_SAMPLES..]);
+        Ok(probability)
+    }
+
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32> {
+        let resampled = self.resampler.process(frame)?;
+        self.pending.extend(resampled);
+
+        let mut best = None;
+        while self.pending.len() >= WINDOW_SAMPLES {
+            let window: Vec<f32> = self.pending.drain(..WINDOW_SAMPLES).collect();
+            let probability = self.infer(&window)?;
+            best = Some(best.map_or(probability, |b: f32| b.max(probability)));
+        }
+        // Frames shorter than a model window reuse the last score
+        if let Some(probability) = best {
+            self.last_probability = probability;
+        }
+        Ok(self.last_probability)
+    }
+}
+
+impl VoiceDetector for SileroVad {
+    fn process_frame(&mut self, frame: &[f32]) -> VadState {
+        let probability = match self.speech_probability(frame) {
+            Ok(probability) => probability,
+            Err(e) => {
+                log::error!("Silero VAD inference failed: {}", e);
+                0.0
+            }
+        };
+        self.hysteresis.process(probability)
+    }
+
+    fn update_stop_window(&mut self, stop_window_ms: u64, frame_rate_ms: u64) {
+        self.hysteresis.update_stop_window(stop_window_ms, frame_rate_ms);
+    }
+
+    fn update_start_window(&mut self, start_window_ms: u64, frame_rate_ms: u64) {
+        self.hysteresis.update_start_window(start_window_ms, frame_rate_ms);
+    }
+
+    fn backend(&self) -> VadBackend {
+        VadBackend::Silero
+    }
+}
@@ -0,0 +1,271 @@
+use anyhow::{Context, Result, anyhow, bail};
+use log::{info, error};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::downmix::{ChannelMix, Downmixer};
+use super::ring::SampleSink;
+
+/// Anything that feeds mono f32 samples into the engine's ring buffer.
+///
+/// Sources are constructed with a `SampleSink` and push samples from their own
+/// thread (or audio callback) until dropped, notifying the sink after each batch.
+pub trait AudioSource {
+    /// Sample rate of the samples pushed into the ring buffer.
+    fn sample_rate(&self) -> u32;
+
+    /// True once the source can no longer deliver audio and should be rebuilt.
+    fn has_failed(&self) -> bool;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PcmEncoding {
+    F32,
+    I16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileFormat {
+    Wav,
+    /// Headerless little-endian interleaved PCM.
+    Raw { encoding: PcmEncoding, sample_rate: u32, channels: u16 },
+}
+
+impl FileFormat {
+    /// Parses a raw PCM description of the form `encoding:sample_rate:channels`, e.g. `i16:44100:2`.
+    pub fn parse_raw(spec: &str) -> Result<Self> {
+        let parts: Vec<&str> = spec.split(':').collect();
+        if parts.len() != 3 {
+            bail!("Raw PCM spec must look like 'f32:48000:1', got '{}'", spec);
+        }
+        let encoding = match parts[0].to_lowercase().as_str() {
+            "f32" => PcmEncoding::F32,
+            "i16" => PcmEncoding::I16,
+            other => bail!("Unsupported raw PCM encoding '{}'", other),
+        };
+        let sample_rate = parts[1].parse().context("Invalid raw PCM sample rate")?;
+        let channels: u16 = parts[2].parse().context("Invalid raw PCM channel count")?;
+        if sample_rate == 0 || channels == 0 {
+            bail!("Raw PCM sample rate and channel count must be non-zero");
+        }
+        Ok(FileFormat::Raw { encoding, sample_rate, channels })
+    }
+}
+
+/// Replays a WAV or raw PCM file into the ring buffer.
+///
+/// `speed` of 1.0 paces samples in real time, larger values replay faster, and 0.0
+/// pushes as fast as the consumer drains the buffer. Once the file is exhausted the
+/// source keeps delivering silence in real time, like a muted microphone, so the
+/// VAD can close the final utterance.
+pub struct FileSource {
+    sample_rate: u32,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl FileSource {
+    pub fn open<P: AsRef<Path>>(path: P, format: FileFormat, speed: f32, sink: SampleSink) -> Result<Self> {
+        let path = path.as_ref();
+        let (samples, sample_rate) = match format {
+            FileFormat::Wav => read_wav(path)?,
+            FileFormat::Raw { encoding, sample_rate, channels } => {
+                let bytes = std::fs::read(path)
+                    .with_context(|| format!("Failed to read {:?}", path))?;
+                (downmix(&decode_raw(&bytes, encoding), channels as usize, sample_rate), sample_rate)
+            }
+        };
+
+        info!("Replaying {:?}: {} samples at {}Hz (speed {}x)", path, samples.len(), sample_rate, speed);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_clone = stop.clone();
+
+        let handle = thread::spawn(move || {
+            feed(samples, sample_rate, speed, sink, stop_clone);
+        });
+
+        Ok(Self { sample_rate, stop, handle: Some(handle) })
+    }
+}
+
+impl AudioSource for FileSource {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn has_failed(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for FileSource {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                error!("File replay thread panicked");
+            }
+        }
+    }
+}
+
+const REPLAY_CHUNK_MS: u64 = 10;
+
+fn feed(samples: Vec<f32>, sample_rate: u32, speed: f32, mut sink: SampleSink, stop: Arc<AtomicBool>) {
+    let chunk_len = ((sample_rate as u64 * REPLAY_CHUNK_MS / 1000) as usize).max(1);
+    let start = Instant::now();
+    let mut pushed = 0usize;
+
+    for chunk in samples.chunks(chunk_len) {
+        for &sample in chunk {
+            // Unlike a live microphone, a replay must not drop samples: wait for the consumer.
+            while sink.is_full() {
+                sink.notify();
+                if stop.load(Ordering::Relaxed) { return; }
+                thread::sleep(Duration::from_millis(1));
+            }
+            sink.push(sample);
+        }
+        sink.notify();
+        pushed += chunk.len();
+
+        if speed > 0.0 {
+            let due = Duration::from_secs_f64(pushed as f64 / sample_rate as f64 / speed as f64);
+            if let Some(wait) = due.checked_sub(start.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+        if stop.load(Ordering::Relaxed) { return; }
+    }
+
+    info!("Replay finished after {} samples", pushed);
+
+    while !stop.load(Ordering::Relaxed) {
+        for _ in 0..chunk_len {
+            sink.push(0.0);
+        }
+        sink.notify();
+        thread::sleep(Duration::from_millis(REPLAY_CHUNK_MS));
+    }
+}
+
+fn read_wav(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let mut reader = hound::WavReader::open(path)
+        .with_context(|| format!("Failed to open WAV file {:?}", path))?;
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>()
+            .collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    if spec.channels == 0 {
+        return Err(anyhow!("WAV file {:?} has no channels", path));
+    }
+    Ok((downmix(&interleaved, spec.channels as usize, spec.sample_rate), spec.sample_rate))
+}
+
+fn decode_raw(bytes: &[u8], encoding: PcmEncoding) -> Vec<f32> {
+    match encoding {
+        PcmEncoding::F32 => bytes.chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        PcmEncoding::I16 => bytes.chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect(),
+    }
+}
+
+fn downmix(interleaved: &[f32], channels: usize, sample_rate: u32) -> Vec<f32> {
+    let mut downmixer = Downmixer::new(channels, ChannelMix::Average, sample_rate);
+    interleaved.chunks_exact(channels.max(1))
+        .map(|frame| downmixer.process_frame(frame))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::segmenter::SpeechSegmenter;
+    use crate::audio::resample::{AudioResampler, ResampleQuality};
+    use crate::audio::ring::{sample_ring, FrameStatus};
+    use crate::audio::vad::EnergyVad;
+
+    #[test]
+    fn test_parse_raw_spec() {
+        assert_eq!(
+            FileFormat::parse_raw("i16:44100:2").unwrap(),
+            FileFormat::Raw { encoding: PcmEncoding::I16, sample_rate: 44100, channels: 2 }
+        );
+        assert!(FileFormat::parse_raw("f64:44100:2").is_err());
+        assert!(FileFormat::parse_raw("f32:0:1").is_err());
+        assert!(FileFormat::parse_raw("f32:16000").is_err());
+    }
+
+    #[test]
+    fn test_raw_decoding_downmixes_channels() {
+        let bytes: Vec<u8> = [16384i16, -16384, 32767, 32767]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let mono = downmix(&decode_raw(&bytes, PcmEncoding::I16), 2, 16000);
+        assert_eq!(mono.len(), 2);
+        assert!(mono[0].abs() < 1e-6);
+        assert!((mono[1] - 1.0).abs() < 1e-3);
+    }
+
+    /// Replays a stereo 48kHz WAV (silence, tone, silence) through the segmenter headlessly.
+    #[test]
+    fn test_wav_replay_through_segmenter() {
+        let rate = 48000u32;
+        let path = std::env::temp_dir().join(format!("flowtype-replay-{}.wav", std::process::id()));
+        let spec = hound::WavSpec { channels: 2, sample_rate: rate, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        let total = rate as usize; // 1 second
+        for i in 0..total {
+            let in_tone = (rate as usize / 4..rate as usize / 2).contains(&i);
+            let s = if in_tone { ((i as f32 * 0.05).sin() * 16000.0) as i16 } else { 0 };
+            writer.write_sample(s).unwrap();
+            writer.write_sample(s).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (sink, mut reader) = sample_ring(rate as usize);
+        let source = FileSource::open(&path, FileFormat::Wav, 0.0, sink).unwrap();
+        assert_eq!(source.sample_rate(), rate);
+
+        let frame_len = (rate / 1000 * 30) as usize;
+        let vad = EnergyVad::new(0.05, 0.02, 60, 150, 30);
+        let resampler = AudioResampler::new(rate as usize, 16000, frame_len, ResampleQuality::Fast).unwrap();
+        let mut segmenter = SpeechSegmenter::new(Box::new(vad), resampler, 30, 90);
+        let mut frame = vec![0.0f32; frame_len];
+        let mut utterances = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+
+        while utterances.is_empty() && Instant::now() < deadline {
+            if let FrameStatus::Ready = reader.read_frame(&mut frame, Duration::from_millis(100)) {
+                if let Some(u) = segmenter.process(&frame).unwrap().utterance {
+                    utterances.push(u);
+                }
+            }
+        }
+        assert_eq!(reader.dropped_samples(), 0);
+        drop(source);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(utterances.len(), 1);
+        // 250ms tone, plus pre-roll and the stop window tail
+        let secs = utterances[0].len() as f32 / 16000.0;
+        assert!(secs > 0.25 && secs < 0.6, "utterance was {}s", secs);
+    }
+}
@@ -0,0 +1,207 @@
+use super::vad::{EnergyVad, VadBackend, VadState, VadThresholds, VoiceDetector};
+
+/// Telephone speech band. Voiced speech keeps most of its energy in here,
+/// while fan hum sits below it and keyboard clicks spread far above it.
+const SPEECH_BAND_LOW_HZ: f32 = 300.0;
+const SPEECH_BAND_HIGH_HZ: f32 = 3400.0;
+/// Share of the frame's energy that has to fall inside the speech band.
+const MIN_BAND_RATIO: f32 = 0.5;
+/// Zero crossings per second, roughly the dominant frequency times two.
+const MIN_CROSSING_RATE: f32 = 100.0;
+const MAX_CROSSING_RATE: f32 = 6000.0;
+/// Peak-to-RMS ratio. Clicks and taps are short spikes in an otherwise quiet frame.
+const MAX_CREST_FACTOR: f32 = 12.0;
+
+/// Second-order IIR section (RBJ cookbook), direct form I.
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Biquad {
+    fn high_pass(sample_rate: f32, cutoff: f32) -> Self {
+        let (cos, alpha) = Self::prewarp(sample_rate, cutoff);
+        let a0 = 1.0 + alpha;
+        Self::normalized([(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0], [a0, -2.0 * cos, 1.0 - alpha])
+    }
+
+    fn low_pass(sample_rate: f32, cutoff: f32) -> Self {
+        let (cos, alpha) = Self::prewarp(sample_rate, cutoff);
+        let a0 = 1.0 + alpha;
+        Self::normalized([(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0], [a0, -2.0 * cos, 1.0 - alpha])
+    }
+
+    /// Butterworth Q. Cutoffs above Nyquist are clamped just below it.
+    fn prewarp(sample_rate: f32, cutoff: f32) -> (f32, f32) {
+        let cutoff = cutoff.min(sample_rate * 0.45);
+        let w0 = 2.0 * std::f32::consts::PI * cutoff / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2))
+    }
+
+    fn normalized(b: [f32; 3], a: [f32; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2 - self.a1 * self.y1 - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// Energy detector that only counts frames whose spectrum looks like speech.
+///
+/// Frames that fail the speech-band, zero-crossing or crest-factor checks are fed to the
+/// hysteresis as silence, so steady noise and clatter never open an utterance on their own.
+pub struct SpectralVad {
+    sample_rate: f32,
+    high_pass: Biquad,
+    low_pass: Biquad,
+    hysteresis: EnergyVad,
+}
+
+impl SpectralVad {
+    pub fn new(
+        sample_rate: u32,
+        start_threshold: f32,
+        stop_threshold: f32,
+        start_window_ms: u64,
+        stop_window_ms: u64,
+        frame_rate_ms: u64,
+    ) -> Self {
+        let rate = sample_rate as f32;
+        Self {
+            sample_rate: rate,
+            high_pass: Biquad::high_pass(rate, SPEECH_BAND_LOW_HZ),
+            low_pass: Biquad::low_pass(rate, SPEECH_BAND_HIGH_HZ),
+            hysteresis: EnergyVad::new(start_threshold, stop_threshold, start_window_ms, stop_window_ms, frame_rate_ms),
+        }
+    }
+
+    /// Returns the frame's RMS and whether it looks like speech.
+    fn analyze(&mut self, frame: &[f32]) -> (f32, bool) {
+        if frame.len() < 2 {
+            return (0.0, false);
+        }
+
+        let mut total = 0.0;
+        let mut band = 0.0;
+        let mut peak = 0.0f32;
+        let mut crossings = 0;
+        for (i, &s) in frame.iter().enumerate() {
+            let filtered = self.low_pass.process(self.high_pass.process(s));
+            total += s * s;
+            band += filtered * filtered;
+            peak = peak.max(s.abs());
+            if i > 0 && (s >= 0.0) != (frame[i - 1] >= 0.0) {
+                crossings += 1;
+            }
+        }
+        if total <= f32::EPSILON {
+            return (0.0, false);
+        }
+
+        let rms = (total / frame.len() as f32).sqrt();
+        let band_ratio = band / total;
+        let crossing_rate = crossings as f32 * self.sample_rate / (frame.len() - 1) as f32;
+        let crest = peak / rms;
+
+        let speech_like = band_ratio >= MIN_BAND_RATIO
+            && (MIN_CROSSING_RATE..=MAX_CROSSING_RATE).contains(&crossing_rate)
+            && crest <= MAX_CREST_FACTOR;
+        (rms, speech_like)
+    }
+}
+
+impl VoiceDetector for SpectralVad {
+    fn process_frame(&mut self, frame: &[f32]) -> VadState {
+        let (rms, speech_like) = self.analyze(frame);
+        // The floor follows the room as it is, noise included
+        self.hysteresis.track_noise(rms);
+        self.hysteresis.process(if speech_like { rms } else { 0.0 })
+    }
+
+    fn update_stop_window(&mut self, stop_window_ms: u64, frame_rate_ms: u64) {
+        self.hysteresis.update_stop_window(stop_window_ms, frame_rate_ms);
+    }
+
+    fn update_start_window(&mut self, start_window_ms: u64, frame_rate_ms: u64) {
+        self.hysteresis.update_start_window(start_window_ms, frame_rate_ms);
+    }
+
+    fn backend(&self) -> VadBackend {
+        VadBackend::Spectral
+    }
+
+    fn thresholds(&self) -> Option<VadThresholds> {
+        Some(self.hysteresis.thresholds())
+    }
+
+    fn set_thresholds(&mut self, thresholds: VadThresholds) {
+        self.hysteresis.set_thresholds(thresholds);
+    }
+
+    fn set_adaptive(&mut self, adaptive: bool) {
+        self.hysteresis.set_adaptive(adaptive);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48000;
+    const FRAME: usize = 1440; // 30ms
+
+    fn tone(freq: f32, amplitude: f32, offset: usize) -> Vec<f32> {
+        (offset..offset + FRAME)
+            .map(|i| (i as f32 * freq * 2.0 * std::f32::consts::PI / RATE as f32).sin() * amplitude)
+            .collect()
+    }
+
+    fn detects_speech(mut next_frame: impl FnMut(usize) -> Vec<f32>) -> bool {
+        let mut vad = SpectralVad::new(RATE, 0.008, 0.005, 90, 300, 30);
+        (0..20).any(|i| matches!(vad.process_frame(&next_frame(i * FRAME)), VadState::Speaking))
+    }
+
+    #[test]
+    fn test_speech_band_tone_triggers() {
+        assert!(detects_speech(|offset| tone(800.0, 0.2, offset)));
+    }
+
+    #[test]
+    fn test_fan_hum_is_ignored() {
+        assert!(!detects_speech(|offset| tone(60.0, 0.3, offset)));
+    }
+
+    #[test]
+    fn test_clicks_are_ignored() {
+        // A sharp tap every frame, silence in between
+        assert!(!detects_speech(|_| {
+            let mut frame = vec![0.0f32; FRAME];
+            frame[100] = 0.9;
+            frame[101] = -0.7;
+            frame
+        }));
+    }
+}
@@ -1,6 +1,5 @@
 use std::collections::VecDeque;
-
-
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy)]
 pub enum VadState {
@@ -8,16 +7,94 @@ pub enum VadState {
     Speaking,
 }
 
+/// Which voice detector drives segmentation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VadBackend {
+    /// RMS threshold. Cheap, but any loud noise counts as speech.
+    #[default]
+    Energy,
+    /// RMS gated by speech-band energy, zero-crossing rate and crest factor.
+    Spectral,
+    /// Silero neural VAD (ONNX, CPU).
+    Silero,
+}
+
+/// Thresholds are set this far above the noise floor (x3 ~ +9.5dB, x2 ~ +6dB).
+const START_MARGIN: f32 = 3.0;
+const STOP_MARGIN: f32 = 2.0;
+/// Lower bounds so a dead-quiet room doesn't make every breath count as speech.
+const MIN_START_THRESHOLD: f32 = 0.003;
+const MIN_STOP_THRESHOLD: f32 = 0.002;
+/// Per-frame smoothing of the adaptive floor. It drops quickly when the room gets
+/// quieter and rises slowly, so pauses in speech don't drag it up.
+const FLOOR_FALL_RATE: f32 = 0.05;
+const FLOOR_RISE_RATE: f32 = 0.005;
+/// How long a "speech" level must hold without dipping before it's taken as noise.
+/// Real speech drops to the floor between words well within this.
+const NOISE_WINDOW_MS: u64 = 3000;
+
+/// RMS levels used by energy-based detectors.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VadThresholds {
+    pub noise_floor: f32,
+    pub start_threshold: f32,
+    pub stop_threshold: f32,
+}
+
+impl VadThresholds {
+    pub fn from_noise_floor(noise_floor: f32) -> Self {
+        Self {
+            noise_floor,
+            start_threshold: (noise_floor * START_MARGIN).max(MIN_START_THRESHOLD),
+            stop_threshold: (noise_floor * STOP_MARGIN).max(MIN_STOP_THRESHOLD),
+        }
+    }
+}
+
+impl Default for VadThresholds {
+    /// The fixed thresholds FlowType has always shipped with.
+    fn default() -> Self {
+        Self { noise_floor: 0.008 / START_MARGIN, start_threshold: 0.008, stop_threshold: 0.005 }
+    }
+}
+
+/// A voice activity detector fed one capture frame at a time.
+pub trait VoiceDetector: Send {
+    /// Feeds one frame at the capture sample rate and returns the smoothed state.
+    fn process_frame(&mut self, frame: &[f32]) -> VadState;
+
+    fn update_stop_window(&mut self, stop_window_ms: u64, frame_rate_ms: u64);
+
+    fn update_start_window(&mut self, start_window_ms: u64, frame_rate_ms: u64);
+
+    fn backend(&self) -> VadBackend;
+
+    /// Current RMS thresholds, or `None` if the detector isn't energy-based.
+    fn thresholds(&self) -> Option<VadThresholds> {
+        None
+    }
+
+    fn set_thresholds(&mut self, _thresholds: VadThresholds) {}
+
+    /// Follow the noise floor during silence and move the thresholds with it.
+    fn set_adaptive(&mut self, _adaptive: bool) {}
+}
+
 pub struct EnergyVad {
     // Config
     start_threshold: f32,
     stop_threshold: f32,
     start_window_frames: usize,
     stop_window_frames: usize,
+    adaptive: bool,
+    noise_window_frames: usize,
     
     // State
     current_state: VadState,
     energy_history: VecDeque<f32>,
+    noise_floor: f32,
+    speaking_levels: VecDeque<f32>,
 }
 
 impl EnergyVad {
@@ -36,8 +113,12 @@ impl EnergyVad {
             stop_threshold,
             start_window_frames: start_frames,
             stop_window_frames: stop_frames,
+            adaptive: false,
+            noise_window_frames: (NOISE_WINDOW_MS / frame_rate_ms).max(1) as usize,
             current_state: VadState::Silence,
             energy_history: VecDeque::with_capacity(std::cmp::max(start_frames, stop_frames)),
+            noise_floor: start_threshold / START_MARGIN,
+            speaking_levels: VecDeque::new(),
         }
     }
 
@@ -98,6 +179,99 @@ impl EnergyVad {
     pub fn update_stop_window(&mut self, stop_window_ms: u64, frame_rate_ms: u64) {
         self.stop_window_frames = (stop_window_ms / frame_rate_ms).max(1) as usize;
     }
+
+    pub fn update_start_window(&mut self, start_window_ms: u64, frame_rate_ms: u64) {
+        self.start_window_frames = (start_window_ms / frame_rate_ms).max(1) as usize;
+    }
+
+    pub fn thresholds(&self) -> VadThresholds {
+        VadThresholds {
+            noise_floor: self.noise_floor,
+            start_threshold: self.start_threshold,
+            stop_threshold: self.stop_threshold,
+        }
+    }
+
+    pub fn set_thresholds(&mut self, thresholds: VadThresholds) {
+        self.noise_floor = thresholds.noise_floor;
+        self.start_threshold = thresholds.start_threshold;
+        self.stop_threshold = thresholds.stop_threshold;
+    }
+
+    pub fn set_adaptive(&mut self, adaptive: bool) {
+        self.adaptive = adaptive;
+    }
+
+    /// Updates the rolling noise-floor estimate with a frame's RMS (adaptive mode only).
+    /// In silence, frames below the start threshold count, so speech onsets are left out.
+    /// While speaking, the floor creeps up toward the quietest level of the last
+    /// `NOISE_WINDOW_MS`, so a step rise in ambient noise (a fan, AC) isn't taken for
+    /// endless speech.
+    pub fn track_noise(&mut self, rms: f32) {
+        if !self.adaptive {
+            return;
+        }
+        let level = match self.current_state {
+            VadState::Silence => {
+                self.speaking_levels.clear();
+                if rms >= self.start_threshold {
+                    return;
+                }
+                rms
+            }
+            VadState::Speaking => {
+                if self.speaking_levels.len() >= self.noise_window_frames {
+                    self.speaking_levels.pop_front();
+                }
+                self.speaking_levels.push_back(rms);
+                if self.speaking_levels.len() < self.noise_window_frames {
+                    return;
+                }
+                let quietest = self.speaking_levels.iter().copied().fold(f32::INFINITY, f32::min);
+                if quietest <= self.noise_floor {
+                    return;
+                }
+                quietest
+            }
+        };
+        let rate = if level < self.noise_floor { FLOOR_FALL_RATE } else { FLOOR_RISE_RATE };
+        self.noise_floor += (level - self.noise_floor) * rate;
+        let adapted = VadThresholds::from_noise_floor(self.noise_floor);
+        self.start_threshold = adapted.start_threshold;
+        self.stop_threshold = adapted.stop_threshold;
+    }
+}
+
+impl VoiceDetector for EnergyVad {
+    fn process_frame(&mut self, frame: &[f32]) -> VadState {
+        let rms = Self::calculate_rms(frame);
+        self.track_noise(rms);
+        self.process(rms)
+    }
+
+    fn update_stop_window(&mut self, stop_window_ms: u64, frame_rate_ms: u64) {
+        EnergyVad::update_stop_window(self, stop_window_ms, frame_rate_ms);
+    }
+
+    fn update_start_window(&mut self, start_window_ms: u64, frame_rate_ms: u64) {
+        EnergyVad::update_start_window(self, start_window_ms, frame_rate_ms);
+    }
+
+    fn backend(&self) -> VadBackend {
+        VadBackend::Energy
+    }
+
+    fn thresholds(&self) -> Option<VadThresholds> {
+        Some(EnergyVad::thresholds(self))
+    }
+
+    fn set_thresholds(&mut self, thresholds: VadThresholds) {
+        EnergyVad::set_thresholds(self, thresholds);
+    }
+
+    fn set_adaptive(&mut self, adaptive: bool) {
+        EnergyVad::set_adaptive(self, adaptive);
+    }
 }
 
 #[cfg(test)]
@@ -145,4 +319,60 @@ mod tests {
         let state = vad.process(0.1); // 5 -> Drop!
         assert!(matches!(state, VadState::Silence));
     }
+
+    #[test]
+    fn test_adaptive_thresholds_follow_noise_floor() {
+        let mut vad = EnergyVad::new(0.008, 0.005, 30, 50, 10);
+        vad.set_adaptive(true);
+
+        // Quieter room: floor drops, thresholds clamp at their minimums
+        for _ in 0..500 {
+            vad.track_noise(0.0005);
+        }
+        let quiet = vad.thresholds();
+        assert!((quiet.noise_floor - 0.0005).abs() < 1e-4);
+        assert_eq!(quiet.start_threshold, MIN_START_THRESHOLD);
+
+        // Louder room: floor rises slowly toward the new level
+        for _ in 0..2000 {
+            vad.track_noise(0.002);
+        }
+        let noisy = vad.thresholds();
+        assert!((noisy.noise_floor - 0.002).abs() < 1e-4);
+        assert!((noisy.start_threshold - 0.006).abs() < 1e-3);
+        assert!(noisy.stop_threshold < noisy.start_threshold);
+
+        // Nothing moves at the start of speech
+        vad.set_thresholds(VadThresholds::from_noise_floor(0.01));
+        for _ in 0..3 {
+            vad.process(0.5);
+        }
+        vad.track_noise(0.0);
+        assert_eq!(vad.thresholds().noise_floor, 0.01);
+    }
+
+    #[test]
+    fn test_adaptive_floor_learns_sustained_noise_step() {
+        let mut vad = EnergyVad::new(0.008, 0.005, 30, 50, 10);
+        vad.set_adaptive(true);
+        for _ in 0..500 {
+            vad.process_frame(&[0.001; 16]);
+        }
+        let quiet = vad.thresholds().noise_floor;
+
+        // Speech dips between words, so the floor stays put
+        for i in 0..1000 {
+            let level = if i % 10 < 8 { 0.1 } else { 0.001 };
+            vad.process_frame(&[level; 16]);
+        }
+        assert!(matches!(vad.current_state, VadState::Speaking));
+        assert!(vad.thresholds().noise_floor <= quiet);
+
+        // A fan turns on: the level steps up and never dips
+        for _ in 0..1000 {
+            vad.process_frame(&[0.02; 16]);
+        }
+        assert!(matches!(vad.current_state, VadState::Silence));
+        assert!((vad.thresholds().noise_floor - 0.02).abs() < 1e-3);
+    }
 }
@@ -13,6 +13,12 @@ mod platform {
         automation: Option<IUIAutomation>,
     }
 
+    pub fn focused_window_id() -> Option<u64> {
+        use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+        let hwnd = unsafe { GetForegroundWindow() };
+        (hwnd.0 != 0).then_some(hwnd.0 as u64)
+    }
+
     #[derive(Debug)]
     enum WindowContext {
         VSCode,
@@ -163,30 +169,20 @@ mod platform {
         // Main injection entry-point
         // ============================================================
 
-        pub fn inject(&self, text: &str, allow_commands: bool, shortcuts: &HashMap<String, String>, disable_punctuation: bool) -> Result<()> {
+        pub fn inject(&self, text: &str, allow_commands: bool, shortcuts: &HashMap<String, String>) -> Result<()> {
             if text.is_empty() { return Ok(()); }
 
             let mut text_to_inject = text.to_string();
 
-            // 1. Punctuation removal
-            if disable_punctuation {
-                text_to_inject = text_to_inject.chars().filter(|c| !c.is_ascii_punctuation()).collect();
-            }
-
             info!("Injecting (Windows): '{}' (commands: {})", text_to_inject, allow_commands);
 
-            // 2. Determine window context ONCE
+            // 1. Determine window context ONCE
             let ctx = self.get_window_context();
             info!("Window context: {:?}", ctx);
 
-            // 3. Shortcut / command handling
+            // 2. Shortcut / command handling
             if allow_commands {
-                let clean: String = text_to_inject
-                    .trim()
-                    .to_lowercase()
-                    .chars()
-                    .filter(|c| !c.is_ascii_punctuation())
-                    .collect();
+                let clean = shortcut_key(&text_to_inject);
 
                 info!("🎤 Command check: looking for '{}' in {} shortcuts", clean, shortcuts.len());
 
@@ -206,7 +202,7 @@ mod platform {
 
             if text_to_inject.is_empty() { return Ok(()); }
 
-            // 4. Injection strategy — determined by window context
+            // 3. Injection strategy — determined by window context
             match ctx {
                 WindowContext::VSCode => {
                     info!("📝 VS Code → keyboard injection");
@@ -253,9 +249,15 @@ mod platform {
         // ============================================================
 
         fn inject_keyboard_unicode(&self, text: &str) -> Result<()> {
-            use windows::Win32::UI::Input::KeyboardAndMouse::KEYEVENTF_UNICODE;
+            use windows::Win32::UI::Input::KeyboardAndMouse::{KEYEVENTF_UNICODE, VK_RETURN};
             let mut inputs = Vec::new();
             for c in text.encode_utf16() {
+                // Many apps ignore a typed '\n', so line breaks press Enter
+                if c == '\n' as u16 {
+                    inputs.push(INPUT { r#type: INPUT_KEYBOARD, Anonymous: INPUT_0 { ki: KEYBDINPUT { wVk: VK_RETURN, ..Default::default() } } });
+                    inputs.push(INPUT { r#type: INPUT_KEYBOARD, Anonymous: INPUT_0 { ki: KEYBDINPUT { wVk: VK_RETURN, dwFlags: KEYEVENTF_KEYUP, ..Default::default() } } });
+                    continue;
+                }
                 inputs.push(INPUT {
                     r#type: INPUT_KEYBOARD,
                     Anonymous: INPUT_0 {
@@ -345,29 +347,30 @@ mod platform {
         enigo: Enigo,
     }
 
+    /// Not tracked on macOS yet. State that follows the focused window falls back to
+    /// resetting after an idle period there.
+    pub fn focused_window_id() -> Option<u64> {
+        None
+    }
+
     impl PlatformInjector {
         pub fn new() -> Result<Self> {
             let enigo = Enigo::new(&Settings::default()).map_err(|e| anyhow!("Failed to init Enigo: {}", e))?;
             Ok(Self { enigo })
         }
 
-        pub fn inject(&self, text: &str, allow_commands: bool, shortcuts: &HashMap<String, String>, disable_punctuation: bool) -> Result<()> {
+        pub fn inject(&self, text: &str, allow_commands: bool, shortcuts: &HashMap<String, String>) -> Result<()> {
             if text.is_empty() { return Ok(()); }
             
             let mut text_to_inject = text.to_string();
 
-            // 1. Punctuation removal
-            if disable_punctuation {
-                text_to_inject = text_to_inject.chars().filter(|c| !c.is_ascii_punctuation()).collect();
-            }
-
             info!("Injecting (MacOS): '{}' (commands: {})", text_to_inject, allow_commands);
 
             let mut enigo = self.enigo.clone();
 
-            // 2. Shortcut/Command Handling
+            // 1. Shortcut/Command Handling
             if allow_commands {
-                let clean = text_to_inject.trim().to_lowercase();
+                let clean = shortcut_key(&text_to_inject);
                 
                 if let Some(result) = shortcuts.get(&clean) {
                     info!("Shortcut triggered: '{}' -> '{}'", clean, result);
@@ -394,6 +397,17 @@ mod platform {
     }
 }
 
+/// The `shortcuts` key an utterance triggers: "Delete that." -> "delete that".
+pub fn shortcut_key(text: &str) -> String {
+    text.trim().to_lowercase().chars().filter(|c| !c.is_ascii_punctuation()).collect()
+}
+
+/// Identifies the window that currently has focus, so dictation state can follow it.
+/// `None` when it can't be determined.
+pub fn focused_window_id() -> Option<u64> {
+    platform::focused_window_id()
+}
+
 pub struct TextInjector {
     inner: platform::PlatformInjector,
 }
@@ -403,7 +417,7 @@ impl TextInjector {
         Ok(Self { inner: platform::PlatformInjector::new()? })
     }
 
-    pub fn inject(&self, text: &str, allow_commands: bool, shortcuts: &HashMap<String, String>, disable_punctuation: bool) -> Result<()> {
-        self.inner.inject(text, allow_commands, shortcuts, disable_punctuation)
+    pub fn inject(&self, text: &str, allow_commands: bool, shortcuts: &HashMap<String, String>) -> Result<()> {
+        self.inner.inject(text, allow_commands, shortcuts)
     }
 }
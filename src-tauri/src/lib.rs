@@ -2,50 +2,179 @@ mod audio;
 mod model;
 mod transcription;
 mod injector;
+mod text;
 
 use anyhow::Result;
-use ringbuf::HeapRb;
-use std::time::Duration;
-use log::{info, error};
+use std::time::{Duration, Instant};
+use log::{info, warn, error};
 mod settings;
 
 use std::sync::{Arc, atomic::{AtomicBool}, RwLock};
 use std::collections::HashMap;
 use std::thread;
 use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutEvent, ShortcutState};
 
-use audio::capture::AudioCapture;
-use audio::vad::{EnergyVad, VadState};
+use audio::calibration::NoiseCalibrator;
+use audio::capture::{AudioCapture, CaptureConfig};
+use audio::downmix::ChannelMix;
+use audio::resample::{AudioResampler, ResampleQuality};
+use audio::ring::{sample_ring, FrameReader, FrameStatus};
+use audio::segmenter::{ActivationMode, SpeechSegmenter};
+use audio::source::{AudioSource, FileFormat, FileSource};
+use audio::spectral::SpectralVad;
+use audio::vad::{EnergyVad, VadBackend, VadState, VadThresholds, VoiceDetector};
 use model::ModelManager;
-use transcription::TranscriptionEngine;
+use transcription::{AudioChunk, Transcript, TranscriptionEngine, TranscriptionOptions, WhisperBackend};
+use transcription::confidence::{Confidence, ConfidenceFilter};
+use transcription::decoding::{DecodingParams, DecodingPreset};
+use transcription::filter::{self, FilterRule, TextFilter};
+use transcription::http::HttpBackendConfig;
+use transcription::context::ContextTracker;
+use transcription::prompt::{self, GlossaryTerm};
 use injector::TextInjector;
+use text::{Continuity, PipelineConfig, TextPipeline};
+use text::disfluency::{self, DisfluencyConfig};
+use text::itn::ItnOptions;
 
 // Newtype wrappers for Tauri state (each needs unique type to avoid collision)
 struct AutoSpaceState(Arc<AtomicBool>);
 struct AllowCommandsState(Arc<AtomicBool>);
 struct DisablePunctuationState(Arc<AtomicBool>);
+struct SpokenPunctuationState(Arc<AtomicBool>);
+struct InputDeviceState(Arc<RwLock<Option<String>>>);
+struct ChannelMixState(Arc<RwLock<ChannelMix>>);
+struct ResampleQualityState(Arc<RwLock<ResampleQuality>>);
+struct PreferNativeRateState(Arc<AtomicBool>);
+struct VadBackendState(Arc<RwLock<VadBackend>>);
+struct AdaptiveVadState(Arc<AtomicBool>);
+struct MaxUtteranceState(Arc<std::sync::atomic::AtomicU64>);
+struct ActivationModeState(Arc<RwLock<ActivationMode>>);
+/// Whether the activation hotkey currently holds the gate open (push-to-talk / toggle).
+struct HotkeyActiveState(Arc<AtomicBool>);
+struct VadThresholdsState(Arc<RwLock<VadThresholds>>);
+struct VadTuningState(Arc<RwLock<settings::VadTuning>>);
+/// Calibration requests (sampling duration in ms) for the audio loop.
+struct CalibrationState(crossbeam_channel::Sender<u64>);
+struct TranscriptionOptionsState(Arc<RwLock<TranscriptionOptions>>);
+struct ContextState(Arc<std::sync::Mutex<ContextTracker>>);
+struct EngineStatusState(Arc<RwLock<EngineStatusPayload>>);
+struct ItnState(Arc<RwLock<ItnOptions>>);
+struct DisfluencyState(Arc<RwLock<DisfluencyConfig>>);
+struct ModelState {
+    tx_model: crossbeam_channel::Sender<WhisperBackend>,
+    /// Bumped on every request so a slow load can't replace a model chosen after it.
+    generation: Arc<std::sync::Mutex<u64>>,
+}
 
 const SAMPLE_RATE: u32 = 16000; 
 const FRAME_SIZE_MS: u64 = 30;  
 const RINGBUF_SIZE: usize = 16000 * 10; 
+const INITIAL_RECONNECT_BACKOFF_MS: u64 = 500;
+const MAX_RECONNECT_BACKOFF_MS: u64 = 8000;
+const PREFERRED_DEVICE_POLL_MS: u64 = 5000;
+const FRAME_WAIT_TIMEOUT_MS: u64 = 100;
+const AUDIO_METRICS_INTERVAL_MS: u64 = 5000;
+const VAD_THRESHOLDS_INTERVAL_MS: u64 = 1000;
+const DEFAULT_CALIBRATION_SECS: u64 = 3;
+/// How often the open utterance is re-transcribed for interim text.
+const PARTIAL_INTERVAL_MS: u64 = 500;
+/// Glossary recency from transcripts is saved at most this often, off the typing path.
+const GLOSSARY_SAVE_DELAY_MS: u64 = 5000;
 
 #[derive(serde::Serialize, Clone)]
 struct VadPayload {
     state: String,
     rms: f32,
+    backend: VadBackend,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct VadThresholdsPayload {
+    #[serde(flatten)]
+    thresholds: VadThresholds,
+    adaptive: bool,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct VadCalibrationPayload {
+    status: String,
+    thresholds: Option<VadThresholds>,
 }
 
 #[derive(serde::Serialize, Clone)]
 struct TranscriptionPayload {
     text: String,
+    /// Detected language code, when the language is set to "auto".
+    language: Option<String>,
+    /// Whisper's confidence in the final text, overall and per segment (absent for partials).
+    confidence: Option<Confidence>,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct AudioMetricsPayload {
+    dropped_samples: u64,
+    dropped_since_last: u64,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct AudioDevicePayload {
+    device: Option<String>,
+    status: String,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct ModelStatusPayload {
+    model: String,
+    /// "loading", "ready" or "error"
+    status: String,
+    error: Option<String>,
+}
+
+/// Whether the transcription engine can take speech yet. Also kept in state for a UI
+/// that connects after the event was sent.
+#[derive(serde::Serialize, Clone)]
+struct EngineStatusPayload {
+    /// "loading", "ready" or "error"
+    status: String,
+    model: Option<String>,
+    error: Option<String>,
+}
+
+/// Records the engine status and emits `engine-ready` or `engine-error`.
+fn report_engine_status(app: &AppHandle, status: &str, model: Option<String>, error: Option<String>) {
+    let payload = EngineStatusPayload { status: status.to_string(), model, error };
+    *app.state::<EngineStatusState>().0.write().unwrap() = payload.clone();
+    match status {
+        "ready" => app.emit("engine-ready", payload).ok(),
+        "error" => app.emit("engine-error", payload).ok(),
+        _ => None,
+    };
+}
+
+/// Loads a Whisper model and warms it up. A failed warm-up only costs first-utterance latency.
+fn load_model(model_path: &std::path::Path) -> Result<WhisperBackend> {
+    let mut backend = WhisperBackend::new(model_path)?;
+    let t0 = Instant::now();
+    match backend.warm_up() {
+        Ok(()) => info!("🔥 Model warmed up in {:?}", t0.elapsed()),
+        Err(e) => warn!("{:#}", e),
+    }
+    Ok(backend)
 }
 
 pub fn start_engine(app: AppHandle) -> Result<()> {
     info!("Starting FlowType Engine...");
+    app.manage(EngineStatusState(Arc::new(RwLock::new(EngineStatusPayload {
+        status: "loading".to_string(),
+        model: None,
+        error: None,
+    }))));
 
     thread::spawn(move || {
-        if let Err(e) = run_engine_loop(app) {
-            error!("Engine crashed: {}", e);
+        if let Err(e) = run_engine_loop(app.clone()) {
+            error!("Engine crashed: {:#}", e);
+            report_engine_status(&app, "error", None, Some(format!("{:#}", e)));
         }
     });
 
@@ -60,11 +189,26 @@ fn run_engine_loop(app: AppHandle) -> Result<()> {
 
     // 2. Prepare Model
     let model_mgr = ModelManager::new(&app);
-    let model_path = model_mgr.get_or_download_model("tiny.en")?;  
+    let model_name = model::model_for_language(&saved_settings.model, &saved_settings.language, saved_settings.translate);
+    let (model_name, model_path) = match model_mgr.get_or_download_model(&model_name) {
+        Ok(path) => (model_name, path),
+        Err(e) => {
+            let fallback = model::model_for_language(&settings::AppSettings::default().model, &saved_settings.language, saved_settings.translate);
+            warn!("Failed to get model '{}': {}. Falling back to '{}'", model_name, e, fallback);
+            let path = model_mgr.get_or_download_model(&fallback)?;
+            (fallback, path)
+        }
+    };
+    // Keep settings in line with the model actually running
+    if model_name != saved_settings.model {
+        let mut current = mgr.load();
+        current.model = model_name.clone();
+        mgr.save(&current);
+    }
 
     // 3. Setup Channels
-    let (tx_audio, rx_audio) = crossbeam_channel::unbounded::<Vec<f32>>();
-    let (tx_text, rx_text) = crossbeam_channel::unbounded::<String>();
+    let (tx_audio, rx_audio) = crossbeam_channel::unbounded::<AudioChunk>();
+    let (tx_text, rx_text) = crossbeam_channel::unbounded::<Transcript>();
     
     let running = Arc::new(AtomicBool::new(true));
     let running_clone = running.clone();
@@ -85,10 +229,92 @@ fn run_engine_loop(app: AppHandle) -> Result<()> {
     let disable_punctuation_clone = disable_punctuation.clone();
     app.manage(DisablePunctuationState(disable_punctuation.clone()));
 
+    let spoken_punctuation = Arc::new(AtomicBool::new(saved_settings.spoken_punctuation));
+    let spoken_punctuation_clone = spoken_punctuation.clone();
+    app.manage(SpokenPunctuationState(spoken_punctuation.clone()));
+
+    let itn = Arc::new(RwLock::new(saved_settings.itn));
+    let itn_clone = itn.clone();
+    app.manage(ItnState(itn.clone()));
+
+    let disfluency = Arc::new(RwLock::new(saved_settings.disfluency.clone()));
+    let disfluency_clone = disfluency.clone();
+    app.manage(DisfluencyState(disfluency.clone()));
+
     let shortcuts = Arc::new(RwLock::new(saved_settings.shortcuts));
     let shortcuts_clone = shortcuts.clone();
     app.manage(shortcuts.clone());
 
+    let input_device = Arc::new(RwLock::new(saved_settings.input_device.clone()));
+    let input_device_clone = input_device.clone();
+    app.manage(InputDeviceState(input_device.clone()));
+
+    let channel_mix = Arc::new(RwLock::new(saved_settings.channel_mix.clone()));
+    let channel_mix_clone = channel_mix.clone();
+    app.manage(ChannelMixState(channel_mix.clone()));
+
+    let resample_quality = Arc::new(RwLock::new(saved_settings.resample_quality));
+    let resample_quality_clone = resample_quality.clone();
+    app.manage(ResampleQualityState(resample_quality.clone()));
+
+    let prefer_native_rate = Arc::new(AtomicBool::new(saved_settings.prefer_native_rate));
+    let prefer_native_rate_clone = prefer_native_rate.clone();
+    app.manage(PreferNativeRateState(prefer_native_rate.clone()));
+
+    let vad_backend = Arc::new(RwLock::new(saved_settings.vad_backend));
+    let vad_backend_clone = vad_backend.clone();
+    app.manage(VadBackendState(vad_backend.clone()));
+
+    let adaptive_vad = Arc::new(AtomicBool::new(saved_settings.adaptive_vad));
+    let adaptive_vad_clone = adaptive_vad.clone();
+    app.manage(AdaptiveVadState(adaptive_vad.clone()));
+
+    let max_utterance = Arc::new(std::sync::atomic::AtomicU64::new(saved_settings.max_utterance_ms));
+    let max_utterance_clone = max_utterance.clone();
+    app.manage(MaxUtteranceState(max_utterance.clone()));
+
+    let activation_mode = Arc::new(RwLock::new(saved_settings.activation_mode));
+    let activation_mode_clone = activation_mode.clone();
+    app.manage(ActivationModeState(activation_mode.clone()));
+
+    let hotkey_active = Arc::new(AtomicBool::new(false));
+    let hotkey_active_clone = hotkey_active.clone();
+    app.manage(HotkeyActiveState(hotkey_active.clone()));
+
+    if let Err(e) = register_activation_hotkey(&app, saved_settings.activation_mode, &saved_settings.activation_hotkey) {
+        error!("Failed to register activation hotkey: {}", e);
+    }
+
+    let vad_thresholds = Arc::new(RwLock::new(saved_settings.vad_thresholds));
+    let vad_thresholds_clone = vad_thresholds.clone();
+    app.manage(VadThresholdsState(vad_thresholds.clone()));
+
+    let vad_tuning = Arc::new(RwLock::new(saved_settings.vad_tuning));
+    let vad_tuning_clone = vad_tuning.clone();
+    app.manage(VadTuningState(vad_tuning.clone()));
+
+    let (tx_calibrate, rx_calibrate) = crossbeam_channel::unbounded::<u64>();
+    app.manage(CalibrationState(tx_calibrate));
+
+    let transcription_options = Arc::new(RwLock::new(TranscriptionOptions {
+        language: saved_settings.language.clone(),
+        translate: saved_settings.translate,
+        glossary: saved_settings.glossary.clone(),
+        carry_context: saved_settings.carry_context,
+        confidence_filter: saved_settings.confidence_filter,
+        decoding: saved_settings.decoding,
+        // Rules were validated when the settings were loaded
+        text_filter: Arc::new(TextFilter::new(&saved_settings.filter_rules).unwrap_or_default()),
+        http_backend: saved_settings.http_backend.clone(),
+    }));
+    app.manage(TranscriptionOptionsState(transcription_options.clone()));
+    let options_inj = transcription_options.clone();
+
+    let context_tracker = Arc::new(std::sync::Mutex::new(ContextTracker::new(Duration::from_millis(saved_settings.context_idle_reset_ms))));
+    let context_tracker_inj = context_tracker.clone();
+    let context_tracker_clone = context_tracker.clone();
+    app.manage(ContextState(context_tracker.clone()));
+
     // 4. Injector Thread
     let app_handle_inj = app.clone(); 
     thread::spawn(move || {
@@ -99,165 +325,482 @@ fn run_engine_loop(app: AppHandle) -> Result<()> {
                 return;
             }
         };
-        while let Ok(mut text) = rx_text.recv() {
-            // Check for auto-space
-            if auto_space_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                text.push(' ');
-            }
-
-            // Check punctuation filtering EARLY (before display and injection)
-            let punctuations_disabled = disable_punctuation_clone.load(std::sync::atomic::Ordering::Relaxed);
-            if punctuations_disabled {
-                // Replace punctuation with spaces (to maintain word separation)
-                text = text.chars()
-                    .map(|c| if c.is_ascii_punctuation() { ' ' } else { c })
-                    .collect();
-                // Normalize multiple spaces to single space and trim
-                let mut result = String::with_capacity(text.len());
-                let mut last_was_space = false;
-                for c in text.chars() {
-                    if c == ' ' {
-                        if !last_was_space {
-                            result.push(c);
-                        }
-                        last_was_space = true;
-                    } else {
-                        result.push(c);
-                        last_was_space = false;
+        let mut pipeline = TextPipeline::new(PipelineConfig::default());
+        let mut continuity = Continuity::new(context_tracker_inj.lock().unwrap().idle_reset());
+        let mut glossary_save_due: Option<Instant> = None;
+        loop {
+            let received = match glossary_save_due {
+                Some(due) => rx_text.recv_deadline(due),
+                None => rx_text.recv().map_err(|_| crossbeam_channel::RecvTimeoutError::Disconnected),
+            };
+            let transcript = match received {
+                Ok(transcript) => transcript,
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                    save_glossary(&app_handle_inj, &options_inj);
+                    glossary_save_due = None;
+                    continue;
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                    if glossary_save_due.is_some() {
+                        save_glossary(&app_handle_inj, &options_inj);
                     }
+                    break;
+                }
+            };
+            // Interim text is only displayed, never typed
+            let (text, language, confidence) = match transcript {
+                Transcript::Partial(text) => {
+                    app_handle_inj.emit("partial-transcription", TranscriptionPayload { text, language: None, confidence: None }).ok();
+                    continue;
+                }
+                Transcript::Final { text, language, confidence } => (text, language, confidence),
+            };
+
+            let window = injector::focused_window_id();
+
+            // Shape the next prompt: this transcript becomes context, and glossary
+            // terms heard in it move to the front
+            let english = {
+                let mut current_options = options_inj.write().unwrap();
+                if current_options.carry_context {
+                    context_tracker_inj.lock().unwrap().record(&text, window, Instant::now());
                 }
-                text = result.trim().to_string();
-                info!("Punctuation replaced with spaces: '{}'", text);
+                if prompt::mark_used(&mut current_options.glossary, &text, unix_now()) {
+                    glossary_save_due.get_or_insert_with(|| Instant::now() + Duration::from_millis(GLOSSARY_SAVE_DELAY_MS));
+                }
+                current_options.translate || language.as_deref().unwrap_or(current_options.language.as_str()) == "en"
+            };
+
+            let config = PipelineConfig {
+                disfluency: disfluency_clone.read().unwrap().clone(),
+                strip_punctuation: disable_punctuation_clone.load(std::sync::atomic::Ordering::Relaxed),
+                spoken_punctuation: spoken_punctuation_clone.load(std::sync::atomic::Ordering::Relaxed),
+                // Number words are only known in English
+                itn: english.then(|| *itn_clone.read().unwrap()),
+            };
+            if *pipeline.config() != config {
+                pipeline = TextPipeline::new(config);
             }
+            let text = pipeline.process(&text);
 
-            // Emit to frontend (now shows filtered text if punctuation is disabled)
-            app_handle_inj.emit("transcription", TranscriptionPayload { text: text.clone() }).ok();
-            
-            // Inject to OS
+            // Join onto the text typed before, unless this is a command: after one,
+            // what precedes the cursor is unknown
             let commands_enabled = allow_commands_clone.load(std::sync::atomic::Ordering::Relaxed);
             let current_shortcuts = shortcuts_clone.read().unwrap();
+            let text = if commands_enabled && current_shortcuts.contains_key(&injector::shortcut_key(&text)) {
+                continuity.reset();
+                text
+            } else {
+                let auto_space = auto_space_clone.load(std::sync::atomic::Ordering::Relaxed);
+                // Same idle period as the prompt context: after it, the cursor may have moved
+                continuity.set_idle_reset(context_tracker_inj.lock().unwrap().idle_reset());
+                continuity.apply(&text, window, auto_space, Instant::now())
+            };
 
-            if let Err(e) = injector.inject(&text, commands_enabled, &current_shortcuts, punctuations_disabled) {
+            // Emit to frontend: exactly the text that gets typed
+            app_handle_inj.emit("transcription", TranscriptionPayload { text: text.clone(), language, confidence: Some(confidence) }).ok();
+
+            // Inject to OS
+            if let Err(e) = injector.inject(&text, commands_enabled, &current_shortcuts) {
                 error!("Injection failed: {}", e);
             }
         }
     });
 
     // 5. Transcription Thread
-    let _app_handle_tx = app.clone();
+    let (tx_model, rx_model) = crossbeam_channel::unbounded::<WhisperBackend>();
+    app.manage(ModelState { tx_model, generation: Arc::new(std::sync::Mutex::new(0)) });
+    let app_handle_engine = app.clone();
     thread::spawn(move || {
-        let mut engine = match TranscriptionEngine::new(model_path) {
-            Ok(e) => e,
+        // Speech captured meanwhile queues up and is transcribed once the model is ready
+        let mut engine = match load_model(&model_path) {
+            Ok(local) => TranscriptionEngine::new(local),
             Err(e) => {
-                 error!("Failed to init transcription engine: {}", e);
+                 error!("Failed to init transcription engine: {:#}", e);
+                 report_engine_status(&app_handle_engine, "error", Some(model_name), Some(format!("{:#}", e)));
                  return;
             }
         };
-        engine.run(rx_audio, tx_text, running_clone);
+        report_engine_status(&app_handle_engine, "ready", Some(model_name), None);
+        engine.run(rx_audio, rx_model, tx_text, transcription_options, context_tracker, running_clone);
     });
 
-    // 6. RingBuffer
-    let ring = HeapRb::<f32>::new(RINGBUF_SIZE);
-    let (producer, mut consumer) = ring.split();
+    // 6. VAD Settings
+    let mut current_timeout = saved_settings.silence_timeout; 
+    // Calibrated (or default) thresholds. Adaptive mode starts from these on every rebuild.
+    let mut current_thresholds = saved_settings.vad_thresholds;
+    let mut current_tuning = saved_settings.vad_tuning;
+    let mut current_adaptive = saved_settings.adaptive_vad;
+    let mut current_max_utterance = saved_settings.max_utterance_ms;
+    let mut calibrator: Option<NoiseCalibrator> = None;
 
-    // 7. Audio Capture & Resampler
-    let (_capture, source_rate) = AudioCapture::init(producer)?;
-    info!("Audio capture started at {}Hz. Target: {}Hz", source_rate, SAMPLE_RATE);
+    // A replay file, when configured, stands in for the microphone (bug repro / headless runs)
+    let replay = ReplayConfig::from_env()?;
 
-    let mut resampler = audio::resample::AudioResampler::new(
-        source_rate as usize, 
-        SAMPLE_RATE as usize, 
-        (source_rate as u64 * FRAME_SIZE_MS / 1000) as usize
-    )?;
+    // 7. Capture Supervisor
+    // Each pass opens a source, rebuilds the ring buffer, resampler and segmenter for its
+    // sample rate, and runs the audio loop until the stream fails or the capture settings change.
+    let current_capture_config = || CaptureConfig {
+        device: input_device_clone.read().unwrap().clone(),
+        channel_mix: channel_mix_clone.read().unwrap().clone(),
+        preferred_rate: prefer_native_rate_clone.load(std::sync::atomic::Ordering::Relaxed).then_some(SAMPLE_RATE),
+    };
+    let mut backoff = Duration::from_millis(INITIAL_RECONNECT_BACKOFF_MS);
+    loop {
+        let requested = current_capture_config();
+        let requested_quality = *resample_quality_clone.read().unwrap();
+        let requested_backend = *vad_backend_clone.read().unwrap();
+        let opened = match &replay {
+            Some(cfg) => open_replay(cfg),
+            None => open_capture(&requested),
+        };
+        let (source, mut reader, active_device) = match opened {
+            Ok(opened) => opened,
+            Err(e) => {
+                error!("Failed to open audio input: {}. Retrying in {:?}", e, backoff);
+                app.emit("audio-device", AudioDevicePayload { device: requested.device.clone(), status: "reconnecting".to_string() }).ok();
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(Duration::from_millis(MAX_RECONNECT_BACKOFF_MS));
+                continue;
+            }
+        };
+        backoff = Duration::from_millis(INITIAL_RECONNECT_BACKOFF_MS);
+        let source_rate = source.sample_rate();
+        info!("Audio capture started at {}Hz. Target: {}Hz", source_rate, SAMPLE_RATE);
+        app.emit("audio-device", AudioDevicePayload { device: active_device.clone(), status: "connected".to_string() }).ok();
 
-    // 8. VAD
-    let mut vad = EnergyVad::new(0.008, 0.005, 300, 500, FRAME_SIZE_MS);
-    let mut current_timeout = saved_settings.silence_timeout; 
+        // 8. Resampler & Segmentation
+        let chunk_samples = (source_rate as u64 * FRAME_SIZE_MS / 1000) as usize; 
+        let resampler = AudioResampler::new(
+            source_rate as usize, 
+            SAMPLE_RATE as usize, 
+            chunk_samples,
+            requested_quality,
+        )?;
+        let vad = build_detector(&app, requested_backend, source_rate, current_thresholds, current_tuning.start_window_ms, current_timeout);
+        info!("🎯 Voice detector: {:?}", vad.backend());
+        let mut segmenter = SpeechSegmenter::new(vad, resampler, FRAME_SIZE_MS, current_tuning.pre_roll_ms);
+        segmenter.set_post_roll(current_tuning.post_roll_ms);
+        segmenter.set_adaptive(current_adaptive);
+        segmenter.set_max_utterance(current_max_utterance);
 
-    // 9. Loop
-    let chunk_samples = (source_rate as u64 * FRAME_SIZE_MS / 1000) as usize; 
-    let mut buffer = Vec::with_capacity(chunk_samples);
-    let mut voice_buffer = Vec::<f32>::new();
-    
-    let pre_roll_frames = (0.5 * 1000.0 / FRAME_SIZE_MS as f32) as usize; 
-    let mut pre_roll_buffer = std::collections::VecDeque::<Vec<f32>>::with_capacity(pre_roll_frames);
+        // 9. Loop
+        let mut buffer = vec![0.0f32; chunk_samples];
+        let mut last_preferred_check = Instant::now();
+        let mut last_metrics = Instant::now();
+        let mut last_dropped = 0u64;
+        let mut last_thresholds_report = Instant::now();
+        let mut last_partial = Instant::now();
 
-    let mut last_state = VadState::Silence;
+        loop {
+            // Block until a full frame has been captured (or time out to run the checks below)
+            let status = reader.read_frame(&mut buffer, Duration::from_millis(FRAME_WAIT_TIMEOUT_MS));
 
-    loop {
-        // Update timeout dynamically
-        let target_timeout = silence_timeout_clone.load(std::sync::atomic::Ordering::Relaxed);
-        if target_timeout != current_timeout {
-            current_timeout = target_timeout;
-            vad.update_stop_window(current_timeout, FRAME_SIZE_MS);
-            info!("⏳ VAD Silence Timeout updated to {}ms", current_timeout);
-        }
+            // Stream died (device unplugged, driver error) -> reconnect
+            if source.has_failed() || matches!(status, FrameStatus::Closed) {
+                warn!("🎙️ Input stream failed. Reconnecting...");
+                app.emit("audio-device", AudioDevicePayload { device: active_device.clone(), status: "reconnecting".to_string() }).ok();
+                break;
+            }
 
-        std::thread::sleep(Duration::from_millis(FRAME_SIZE_MS));
-        buffer.clear();
-        let available = consumer.len();
-        if available > 0 {
-            for _ in 0..available {
-                if let Some(s) = consumer.pop() { buffer.push(s); }
+            if *vad_backend_clone.read().unwrap() != requested_backend {
+                info!("🎯 Voice detector changed. Rebuilding pipeline...");
+                break;
             }
-        }
 
-        if !buffer.is_empty() {
-             let rms = EnergyVad::calculate_rms(&buffer);
-             let state = vad.process(rms);
+            if *resample_quality_clone.read().unwrap() != requested_quality {
+                info!("🎛️ Resample quality changed. Rebuilding pipeline...");
+                break;
+            }
+
+            if replay.is_none() {
+                // User picked a different device, channel mix or native-rate preference
+                if current_capture_config() != requested {
+                    info!("🎙️ Capture settings changed. Rebuilding stream...");
+                    break;
+                }
+
+                // Running on the default as a fallback -> switch back once the preferred device returns
+                if requested.device.is_some() && active_device != requested.device
+                    && last_preferred_check.elapsed() >= Duration::from_millis(PREFERRED_DEVICE_POLL_MS) {
+                    last_preferred_check = Instant::now();
+                    let available = AudioCapture::list_input_devices().unwrap_or_default();
+                    if available.iter().any(|d| Some(d) == requested.device.as_ref()) {
+                        info!("🎙️ Preferred input device is back. Switching...");
+                        break;
+                    }
+                }
+            }
+
+            // Update timeout dynamically
+            let target_timeout = silence_timeout_clone.load(std::sync::atomic::Ordering::Relaxed);
+            if target_timeout != current_timeout {
+                current_timeout = target_timeout;
+                segmenter.update_stop_window(current_timeout);
+                info!("⏳ VAD Silence Timeout updated to {}ms", current_timeout);
+            }
+
+            let target_max_utterance = max_utterance_clone.load(std::sync::atomic::Ordering::Relaxed);
+            if target_max_utterance != current_max_utterance {
+                current_max_utterance = target_max_utterance;
+                segmenter.set_max_utterance(current_max_utterance);
+                info!("✂️ Max utterance length updated to {}ms", current_max_utterance);
+            }
+
+            // Push-to-talk / toggle: the hotkey drives the gate
+            let mode = *activation_mode_clone.read().unwrap();
+            segmenter.set_activation(mode, hotkey_active_clone.load(std::sync::atomic::Ordering::Relaxed));
 
-             if matches!(state, VadState::Silence) {
-                 if pre_roll_buffer.len() >= pre_roll_frames {
-                     pre_roll_buffer.pop_front();
+            // Thresholds set by hand (or reset to defaults)
+            let target_thresholds = *vad_thresholds_clone.read().unwrap();
+            if target_thresholds != current_thresholds {
+                current_thresholds = target_thresholds;
+                segmenter.set_thresholds(current_thresholds);
+                info!("🎚️ VAD thresholds updated. Start: {:.4}, stop: {:.4}", current_thresholds.start_threshold, current_thresholds.stop_threshold);
+            }
+
+            let target_tuning = *vad_tuning_clone.read().unwrap();
+            if target_tuning != current_tuning {
+                segmenter.update_start_window(target_tuning.start_window_ms);
+                segmenter.set_pre_roll(target_tuning.pre_roll_ms);
+                segmenter.set_post_roll(target_tuning.post_roll_ms);
+                current_tuning = target_tuning;
+                info!("🎚️ VAD tuning updated: {:?}", current_tuning);
+            }
+
+            // Adaptive thresholds toggled. Switching off goes back to the calibrated levels.
+            let target_adaptive = adaptive_vad_clone.load(std::sync::atomic::Ordering::Relaxed);
+            if target_adaptive != current_adaptive {
+                current_adaptive = target_adaptive;
+                segmenter.set_adaptive(current_adaptive);
+                if !current_adaptive {
+                    segmenter.set_thresholds(current_thresholds);
+                }
+                info!("📈 Adaptive VAD thresholds {}", if current_adaptive { "enabled" } else { "disabled" });
+            }
+
+            if calibrator.is_none() {
+                if let Ok(duration_ms) = rx_calibrate.try_recv() {
+                    info!("📏 Calibrating noise floor for {}ms. Stay quiet...", duration_ms);
+                    calibrator = Some(NoiseCalibrator::new(duration_ms, FRAME_SIZE_MS));
+                    app.emit("vad-calibration", VadCalibrationPayload { status: "started".to_string(), thresholds: None }).ok();
+                }
+            }
+
+            // Report the noise floor and thresholds the detector is using
+            if last_thresholds_report.elapsed() >= Duration::from_millis(VAD_THRESHOLDS_INTERVAL_MS) {
+                last_thresholds_report = Instant::now();
+                if let Some(thresholds) = segmenter.thresholds() {
+                    app.emit("vad-thresholds", VadThresholdsPayload { thresholds, adaptive: current_adaptive }).ok();
+                }
+            }
+
+            // Report ring-buffer overruns
+            if last_metrics.elapsed() >= Duration::from_millis(AUDIO_METRICS_INTERVAL_MS) {
+                last_metrics = Instant::now();
+                let dropped = reader.dropped_samples();
+                let dropped_since_last = dropped - last_dropped;
+                last_dropped = dropped;
+                if dropped_since_last > 0 {
+                    warn!("⚠️ Ring buffer overrun: dropped {} samples ({} total)", dropped_since_last, dropped);
+                }
+                app.emit("audio-metrics", AudioMetricsPayload { dropped_samples: dropped, dropped_since_last }).ok();
+            }
+
+            if matches!(status, FrameStatus::Ready) {
+                 let outcome = match segmenter.process(&buffer) {
+                     Ok(outcome) => outcome,
+                     Err(e) => {
+                         error!("Failed to process audio frame: {}", e);
+                         continue;
+                     }
+                 };
+
+                 if let Some(thresholds) = calibrator.as_mut().and_then(|c| c.push(outcome.rms)) {
+                     calibrator = None;
+                     info!("📏 Calibration done. Floor: {:.4}, start: {:.4}, stop: {:.4}", thresholds.noise_floor, thresholds.start_threshold, thresholds.stop_threshold);
+                     current_thresholds = thresholds;
+                     *vad_thresholds_clone.write().unwrap() = thresholds;
+                     segmenter.set_thresholds(thresholds);
+                     let mgr = settings::SettingsManager::new(&app);
+                     let mut current = mgr.load();
+                     current.vad_thresholds = thresholds;
+                     mgr.save(&current);
+                     app.emit("vad-calibration", VadCalibrationPayload { status: "finished".to_string(), thresholds: Some(thresholds) }).ok();
                  }
-                 pre_roll_buffer.push_back(buffer.clone());
-             }
 
-             if matches!(last_state, VadState::Silence) && matches!(state, VadState::Speaking) {
-                 info!("🗣️ Speech started! Prepending {}ms of audio", pre_roll_buffer.len() as u64 * FRAME_SIZE_MS);
-                 for chunk in pre_roll_buffer.iter() {
-                     voice_buffer.extend_from_slice(chunk);
+                 if let Some(utterance) = outcome.utterance {
+                     let rms_utterance = EnergyVad::calculate_rms(&utterance);
+                     let reason = if matches!(outcome.state, VadState::Speaking) { "Length limit hit" } else { "Speech ended" };
+                     info!("🗣️ {}. {} samples at {}Hz (RMS: {:.4}). Sending to Whisper...", reason, utterance.len(), SAMPLE_RATE, rms_utterance);
+                     tx_audio.send(AudioChunk::Final(utterance)).ok();
+                     last_partial = Instant::now();
                  }
-                 pre_roll_buffer.clear(); 
-             }
-
-             if matches!(state, VadState::Speaking) {
-                 voice_buffer.extend_from_slice(&buffer);
-             } 
-             
-             if matches!(last_state, VadState::Speaking) && matches!(state, VadState::Silence) {
-                 if !voice_buffer.is_empty() {
-                     info!("🗣️ Speech ended. Resampling {} samples...", voice_buffer.len());
-                     if let Ok(resampled) = resampler.resample(&voice_buffer) {
-                         let rms_resampled = EnergyVad::calculate_rms(&resampled);
-                         info!("✅ Resampled to {} samples (RMS: {:.4}). Sending to Whisper...", resampled.len(), rms_resampled);
-                         tx_audio.send(resampled).ok();
+
+                 // Interim text for the utterance still being spoken
+                 match segmenter.pending_audio() {
+                     Some(pending) if last_partial.elapsed() >= Duration::from_millis(PARTIAL_INTERVAL_MS) => {
+                         last_partial = Instant::now();
+                         tx_audio.send(AudioChunk::Partial(pending.to_vec())).ok();
                      }
-                     voice_buffer.clear();
+                     Some(_) => {}
+                     None => last_partial = Instant::now(),
                  }
-             }
 
-             if discriminant(&state) != discriminant(&last_state) {
-                 let state_str = match state {
-                     VadState::Speaking => "speaking",
-                     VadState::Silence => "silence",
-                 };
-                 app.emit("vad-update", VadPayload { state: state_str.to_string(), rms }).ok();
-                 last_state = state;
-             } 
+                 if outcome.state_changed {
+                     // Context carried over from another window doesn't apply to this utterance
+                     if matches!(outcome.state, VadState::Speaking) {
+                         context_tracker_clone.lock().unwrap().observe_focus(injector::focused_window_id());
+                     }
+                     let state_str = match outcome.state {
+                         VadState::Speaking => "speaking",
+                         VadState::Silence => "silence",
+                     };
+                     app.emit("vad-update", VadPayload { state: state_str.to_string(), rms: outcome.rms, backend: segmenter.backend() }).ok();
+                 } 
+            }
+        }
+
+        // Don't lose an utterance that was in flight when the device went away
+        match segmenter.flush() {
+            Ok(Some(utterance)) => {
+                info!("🗣️ Flushing {} samples captured before the device switch", utterance.len());
+                tx_audio.send(AudioChunk::Final(utterance)).ok();
+            }
+            Ok(None) => {}
+            Err(e) => error!("Failed to flush in-flight utterance: {}", e),
+        }
+        drop(source);
+    }
+}
+
+/// Opens the preferred input device, falling back to the system default if it is unavailable.
+/// Returns the source, the reader side of a fresh ring buffer and the name of the device
+/// that was actually opened (`None` for the default).
+fn open_capture(config: &CaptureConfig) -> Result<(Box<dyn AudioSource>, FrameReader, Option<String>)> {
+    if config.device.is_some() {
+        let (sink, reader) = sample_ring(RINGBUF_SIZE);
+        match AudioCapture::init(sink, config) {
+            Ok(capture) => return Ok((Box::new(capture), reader, config.device.clone())),
+            Err(e) => warn!("Preferred input device unavailable ({}). Falling back to default.", e),
+        }
+    }
+
+    let (sink, reader) = sample_ring(RINGBUF_SIZE);
+    let fallback = CaptureConfig { device: None, ..config.clone() };
+    let capture = AudioCapture::init(sink, &fallback)?;
+    Ok((Box::new(capture), reader, None))
+}
+
+/// File replay configured through environment variables:
+/// `FLOWTYPE_REPLAY_FILE` (path), `FLOWTYPE_REPLAY_FORMAT` (raw PCM spec such as `i16:44100:2`;
+/// WAV when unset) and `FLOWTYPE_REPLAY_SPEED` (1 = real time, 0 = as fast as possible).
+struct ReplayConfig {
+    path: std::path::PathBuf,
+    format: FileFormat,
+    speed: f32,
+}
+
+impl ReplayConfig {
+    fn from_env() -> Result<Option<Self>> {
+        let Ok(path) = std::env::var("FLOWTYPE_REPLAY_FILE") else { return Ok(None); };
+        let format = match std::env::var("FLOWTYPE_REPLAY_FORMAT") {
+            Ok(spec) => FileFormat::parse_raw(&spec)?,
+            Err(_) => FileFormat::Wav,
+        };
+        let speed = match std::env::var("FLOWTYPE_REPLAY_SPEED") {
+            Ok(v) => v.parse().map_err(|_| anyhow::anyhow!("Invalid FLOWTYPE_REPLAY_SPEED '{}'", v))?,
+            Err(_) => 1.0,
+        };
+        info!("🔁 Replay mode: {} ({:?}, {}x)", path, format, speed);
+        Ok(Some(Self { path: path.into(), format, speed }))
+    }
+}
+
+fn open_replay(cfg: &ReplayConfig) -> Result<(Box<dyn AudioSource>, FrameReader, Option<String>)> {
+    let (sink, reader) = sample_ring(RINGBUF_SIZE);
+    let source = FileSource::open(&cfg.path, cfg.format.clone(), cfg.speed, sink)?;
+    Ok((Box::new(source), reader, Some(cfg.path.display().to_string())))
+}
+
+/// Registers the global hotkey for the manual activation modes. In VAD mode no hotkey is
+/// held, so the key combination stays free for other apps.
+fn register_activation_hotkey(app: &AppHandle, mode: ActivationMode, hotkey: &str) -> Result<()> {
+    let shortcuts = app.global_shortcut();
+    shortcuts.unregister_all()?;
+    if mode == ActivationMode::Vad {
+        return Ok(());
+    }
+    shortcuts.on_shortcut(hotkey, |app, _shortcut, event| on_activation_hotkey(app, event))?;
+    info!("⌨️ Activation hotkey '{}' registered ({:?})", hotkey, mode);
+    Ok(())
+}
+
+fn on_activation_hotkey(app: &AppHandle, event: ShortcutEvent) {
+    let (Some(mode), Some(active)) = (app.try_state::<ActivationModeState>(), app.try_state::<HotkeyActiveState>()) else {
+        return;
+    };
+    let mode = *mode.0.read().unwrap();
+    match (mode, event.state()) {
+        (ActivationMode::PushToTalk, ShortcutState::Pressed) => active.0.store(true, std::sync::atomic::Ordering::Relaxed),
+        (ActivationMode::PushToTalk, ShortcutState::Released) => active.0.store(false, std::sync::atomic::Ordering::Relaxed),
+        (ActivationMode::Toggle, ShortcutState::Pressed) => {
+            active.0.fetch_xor(true, std::sync::atomic::Ordering::Relaxed);
         }
+        _ => {}
     }
 }
 
+/// Builds the configured voice detector, falling back to the energy detector
+/// if the configured one can't be loaded.
+fn build_detector(
+    app: &AppHandle,
+    backend: VadBackend,
+    sample_rate: u32,
+    thresholds: VadThresholds,
+    start_window_ms: u64,
+    stop_window_ms: u64,
+) -> Box<dyn VoiceDetector> {
+    let energy = || -> Box<dyn VoiceDetector> {
+        Box::new(EnergyVad::new(thresholds.start_threshold, thresholds.stop_threshold, start_window_ms, stop_window_ms, FRAME_SIZE_MS))
+    };
+    match backend {
+        VadBackend::Energy => energy(),
+        VadBackend::Spectral => Box::new(SpectralVad::new(
+            sample_rate,
+            thresholds.start_threshold,
+            thresholds.stop_threshold,
+            start_window_ms,
+            stop_window_ms,
+            FRAME_SIZE_MS,
+        )),
+        VadBackend::Silero => match open_silero(app, sample_rate, start_window_ms, stop_window_ms) {
+            Ok(vad) => vad,
+            Err(e) => {
+                warn!("Silero VAD unavailable ({}). Falling back to energy detection.", e);
+                energy()
+            }
+        },
+    }
+}
+
+#[cfg(feature = "silero")]
+fn open_silero(app: &AppHandle, sample_rate: u32, start_window_ms: u64, stop_window_ms: u64) -> Result<Box<dyn VoiceDetector>> {
+    let model_path = ModelManager::new(app).get_or_download_vad_model()?;
+    let vad = audio::silero::SileroVad::new(&model_path, sample_rate, start_window_ms, stop_window_ms, FRAME_SIZE_MS)?;
+    Ok(Box::new(vad))
+}
+
+#[cfg(not(feature = "silero"))]
+fn open_silero(_app: &AppHandle, _sample_rate: u32, _start_window_ms: u64, _stop_window_ms: u64) -> Result<Box<dyn VoiceDetector>> {
+    anyhow::bail!("built without the 'silero' feature")
+}
+
 #[tauri::command]
 fn minimize_window(window: tauri::Window) {
   window.minimize().unwrap();
 }
 
-fn discriminant<T>(v: &T) -> std::mem::Discriminant<T> {
-    std::mem::discriminant(v)
-}
-
 #[tauri::command]
 fn set_auto_space(state: bool, auto_space: tauri::State<'_, AutoSpaceState>, app: tauri::AppHandle) {
     auto_space.0.store(state, std::sync::atomic::Ordering::Relaxed);
@@ -268,12 +811,72 @@ fn set_auto_space(state: bool, auto_space: tauri::State<'_, AutoSpaceState>, app
 }
 
 #[tauri::command]
-fn set_silence_timeout(ms: u64, timeout: tauri::State<'_, Arc<std::sync::atomic::AtomicU64>>, app: tauri::AppHandle) {
+fn set_silence_timeout(ms: u64, timeout: tauri::State<'_, Arc<std::sync::atomic::AtomicU64>>, app: tauri::AppHandle) -> Result<(), String> {
+    settings::validate_silence_timeout(ms).map_err(|e| e.to_string())?;
     timeout.store(ms, std::sync::atomic::Ordering::Relaxed);
     let mgr = settings::SettingsManager::new(&app);
     let mut current = mgr.load();
     current.silence_timeout = ms;
     mgr.save(&current);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_vad_thresholds(
+    start_threshold: f32,
+    stop_threshold: f32,
+    vad_thresholds: tauri::State<'_, VadThresholdsState>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    settings::validate_thresholds(start_threshold, stop_threshold).map_err(|e| e.to_string())?;
+    let thresholds = {
+        let mut current = vad_thresholds.0.write().unwrap();
+        current.start_threshold = start_threshold;
+        current.stop_threshold = stop_threshold;
+        *current
+    };
+    info!("VAD thresholds set to: {:?}", thresholds);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.vad_thresholds = thresholds;
+    mgr.save(&current);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_vad_tuning(tuning: settings::VadTuning, vad_tuning: tauri::State<'_, VadTuningState>, app: tauri::AppHandle) -> Result<(), String> {
+    tuning.validate().map_err(|e| e.to_string())?;
+    info!("VAD tuning set to: {:?}", tuning);
+    *vad_tuning.0.write().unwrap() = tuning;
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.vad_tuning = tuning;
+    mgr.save(&current);
+    Ok(())
+}
+
+/// Restores silence timeout, thresholds and VAD tuning to their defaults.
+/// Returns the updated settings so the UI can refresh.
+#[tauri::command]
+fn reset_vad_tuning(
+    timeout: tauri::State<'_, Arc<std::sync::atomic::AtomicU64>>,
+    vad_thresholds: tauri::State<'_, VadThresholdsState>,
+    vad_tuning: tauri::State<'_, VadTuningState>,
+    app: tauri::AppHandle,
+) -> settings::AppSettings {
+    let defaults = settings::AppSettings::default();
+    timeout.store(defaults.silence_timeout, std::sync::atomic::Ordering::Relaxed);
+    *vad_thresholds.0.write().unwrap() = defaults.vad_thresholds;
+    *vad_tuning.0.write().unwrap() = defaults.vad_tuning;
+    info!("VAD tuning reset to defaults");
+
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.silence_timeout = defaults.silence_timeout;
+    current.vad_thresholds = defaults.vad_thresholds;
+    current.vad_tuning = defaults.vad_tuning;
+    mgr.save(&current);
+    current
 }
 
 #[tauri::command]
@@ -295,6 +898,38 @@ fn set_disable_punctuation(state: bool, disable_punctuation: tauri::State<'_, Di
     mgr.save(&current);
 }
 
+#[tauri::command]
+fn set_spoken_punctuation(state: bool, spoken_punctuation: tauri::State<'_, SpokenPunctuationState>, app: tauri::AppHandle) {
+    spoken_punctuation.0.store(state, std::sync::atomic::Ordering::Relaxed);
+    info!("Spoken Punctuation set to: {}", state);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.spoken_punctuation = state;
+    mgr.save(&current);
+}
+
+#[tauri::command]
+fn set_disfluency(config: DisfluencyConfig, state: tauri::State<'_, DisfluencyState>, app: tauri::AppHandle) -> Result<(), String> {
+    disfluency::validate_fillers(&config.fillers).map_err(|e| e.to_string())?;
+    info!("🧹 Disfluency cleanup set to: {:?} ({} fillers)", config.mode, config.fillers.len());
+    *state.0.write().unwrap() = config.clone();
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.disfluency = config;
+    mgr.save(&current);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_itn(options: ItnOptions, itn: tauri::State<'_, ItnState>, app: tauri::AppHandle) {
+    *itn.0.write().unwrap() = options;
+    info!("🔢 Text normalization set to: {:?}", options);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.itn = options;
+    mgr.save(&current);
+}
+
 #[tauri::command]
 fn upsert_shortcut(key: String, value: String, shortcuts: tauri::State<'_, Arc<RwLock<HashMap<String, String>>>>, app: tauri::AppHandle) {
     let mut current_shortcuts = shortcuts.write().unwrap();
@@ -317,12 +952,412 @@ fn delete_shortcut(key: String, shortcuts: tauri::State<'_, Arc<RwLock<HashMap<S
     mgr.save(&current);
 }
 
+/// Adds a glossary term, or marks an existing one (any casing) as just used.
+#[tauri::command]
+fn upsert_glossary_term(term: String, options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) -> Result<(), String> {
+    let term = term.trim().to_string();
+    if term.is_empty() {
+        return Err("Glossary term is empty".to_string());
+    }
+    if term.contains('\0') {
+        return Err("Glossary term contains a null character".to_string());
+    }
+    let mut current_options = options.0.write().unwrap();
+    let now = unix_now();
+    match current_options.glossary.iter_mut().find(|t| t.term.eq_ignore_ascii_case(&term)) {
+        Some(existing) => {
+            existing.term = term;
+            existing.last_used = now;
+        }
+        None => current_options.glossary.push(GlossaryTerm { term, last_used: now }),
+    }
+
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.glossary = current_options.glossary.clone();
+    mgr.save(&current);
+    Ok(())
+}
+
+#[tauri::command]
+fn delete_glossary_term(term: String, options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) {
+    let mut current_options = options.0.write().unwrap();
+    current_options.glossary.retain(|t| !t.term.eq_ignore_ascii_case(term.trim()));
+
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.glossary = current_options.glossary.clone();
+    mgr.save(&current);
+}
+
+/// Persists the glossary as it is in memory, holding the options lock only to copy it.
+fn save_glossary(app: &tauri::AppHandle, options: &RwLock<TranscriptionOptions>) {
+    let glossary = options.read().unwrap().glossary.clone();
+    let mgr = settings::SettingsManager::new(app);
+    let mut current = mgr.load();
+    current.glossary = glossary;
+    mgr.save(&current);
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[tauri::command]
+fn list_input_devices() -> Result<Vec<String>, String> {
+    AudioCapture::list_input_devices().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_input_device(name: Option<String>, input_device: tauri::State<'_, InputDeviceState>, app: tauri::AppHandle) {
+    info!("Input device set to: {:?}", name);
+    *input_device.0.write().unwrap() = name.clone();
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.input_device = name;
+    mgr.save(&current);
+}
+
+#[tauri::command]
+fn set_channel_mix(mix: ChannelMix, channel_mix: tauri::State<'_, ChannelMixState>, app: tauri::AppHandle) {
+    info!("Channel mix set to: {:?}", mix);
+    *channel_mix.0.write().unwrap() = mix.clone();
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.channel_mix = mix;
+    mgr.save(&current);
+}
+
+#[tauri::command]
+fn set_resample_quality(quality: ResampleQuality, resample_quality: tauri::State<'_, ResampleQualityState>, app: tauri::AppHandle) {
+    info!("Resample quality set to: {:?}", quality);
+    *resample_quality.0.write().unwrap() = quality;
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.resample_quality = quality;
+    mgr.save(&current);
+}
+
+#[tauri::command]
+fn set_prefer_native_rate(state: bool, prefer_native_rate: tauri::State<'_, PreferNativeRateState>, app: tauri::AppHandle) {
+    prefer_native_rate.0.store(state, std::sync::atomic::Ordering::Relaxed);
+    info!("Prefer native 16kHz capture set to: {}", state);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.prefer_native_rate = state;
+    mgr.save(&current);
+}
+
+#[tauri::command]
+fn set_vad_backend(backend: VadBackend, vad_backend: tauri::State<'_, VadBackendState>, app: tauri::AppHandle) {
+    info!("VAD backend set to: {:?}", backend);
+    *vad_backend.0.write().unwrap() = backend;
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.vad_backend = backend;
+    mgr.save(&current);
+}
+
+#[tauri::command]
+fn set_max_utterance(ms: u64, max_utterance: tauri::State<'_, MaxUtteranceState>, app: tauri::AppHandle) -> Result<(), String> {
+    settings::validate_max_utterance(ms).map_err(|e| e.to_string())?;
+    max_utterance.0.store(ms, std::sync::atomic::Ordering::Relaxed);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.max_utterance_ms = ms;
+    mgr.save(&current);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_activation_mode(
+    mode: ActivationMode,
+    activation_mode: tauri::State<'_, ActivationModeState>,
+    hotkey_active: tauri::State<'_, HotkeyActiveState>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    info!("Activation mode set to: {:?}", mode);
+    *activation_mode.0.write().unwrap() = mode;
+    hotkey_active.0.store(false, std::sync::atomic::Ordering::Relaxed);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.activation_mode = mode;
+    mgr.save(&current);
+    register_activation_hotkey(&app, mode, &current.activation_hotkey).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_activation_hotkey(hotkey: String, activation_mode: tauri::State<'_, ActivationModeState>, app: tauri::AppHandle) -> Result<(), String> {
+    let mode = *activation_mode.0.read().unwrap();
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    // Only persist a hotkey that could actually be registered; keep the old one otherwise
+    if let Err(e) = register_activation_hotkey(&app, mode, &hotkey) {
+        register_activation_hotkey(&app, mode, &current.activation_hotkey).ok();
+        return Err(e.to_string());
+    }
+    info!("Activation hotkey set to: {}", hotkey);
+    current.activation_hotkey = hotkey;
+    mgr.save(&current);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_adaptive_vad(state: bool, adaptive_vad: tauri::State<'_, AdaptiveVadState>, app: tauri::AppHandle) {
+    adaptive_vad.0.store(state, std::sync::atomic::Ordering::Relaxed);
+    info!("Adaptive VAD set to: {}", state);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.adaptive_vad = state;
+    mgr.save(&current);
+}
+
+/// Samples ambient noise for a few seconds and derives new thresholds from it.
+/// Progress and the result arrive as `vad-calibration` events.
+#[tauri::command]
+fn calibrate_vad(seconds: Option<u64>, calibration: tauri::State<'_, CalibrationState>) -> Result<(), String> {
+    let seconds = seconds.unwrap_or(DEFAULT_CALIBRATION_SECS).clamp(1, 30);
+    calibration.0.send(seconds * 1000).map_err(|e| e.to_string())
+}
+
+/// Switches the Whisper model. Downloading and loading happen in the background while the
+/// current model keeps transcribing; progress arrives as `model-status` events.
+#[tauri::command]
+fn set_model(
+    model: String,
+    model_state: tauri::State<'_, ModelState>,
+    options: tauri::State<'_, TranscriptionOptionsState>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    if !model::AVAILABLE_MODELS.contains(&model.as_str()) {
+        return Err(format!("Unknown Whisper model '{}'", model));
+    }
+    // An English-only pick is upgraded when another language or translation is selected
+    let model = {
+        let options = options.0.read().unwrap();
+        model::model_for_language(&model, &options.language, options.translate)
+    };
+    switch_model(app, &model_state, model);
+    Ok(())
+}
+
+/// Loads `model` in the background and hands it to the transcription thread.
+fn switch_model(app: AppHandle, model_state: &ModelState, model: String) {
+    let generation = {
+        let mut current = model_state.generation.lock().unwrap();
+        *current += 1;
+        *current
+    };
+    let latest = model_state.generation.clone();
+    let tx_model = model_state.tx_model.clone();
+    info!("Switching Whisper model to '{}'", model);
+
+    thread::spawn(move || {
+        let status = |status: &str, error: Option<String>| {
+            app.emit("model-status", ModelStatusPayload { model: model.clone(), status: status.to_string(), error }).ok();
+        };
+        status("loading", None);
+
+        let backend = ModelManager::new(&app)
+            .get_or_download_model(&model)
+            .and_then(|path| load_model(&path));
+
+        // Held until the swap is queued, so two loads finishing together can't reorder
+        let latest_generation = latest.lock().unwrap();
+        if *latest_generation != generation {
+            info!("Model '{}' loaded after a newer request. Discarding it", model);
+            return;
+        }
+        match backend {
+            Ok(backend) => {
+                if tx_model.send(backend).is_err() {
+                    status("error", Some("Transcription engine is not running".to_string()));
+                    return;
+                }
+                let mgr = settings::SettingsManager::new(&app);
+                let mut current = mgr.load();
+                current.model = model.clone();
+                mgr.save(&current);
+                status("ready", None);
+            }
+            Err(e) => {
+                error!("Failed to load model '{}': {}", model, e);
+                status("error", Some(e.to_string()));
+            }
+        }
+    });
+}
+
+#[tauri::command]
+fn set_carry_context(
+    state: bool,
+    options: tauri::State<'_, TranscriptionOptionsState>,
+    context: tauri::State<'_, ContextState>,
+    app: tauri::AppHandle,
+) {
+    info!("Context carry-over set to: {}", state);
+    options.0.write().unwrap().carry_context = state;
+    if !state {
+        context.0.lock().unwrap().reset();
+    }
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.carry_context = state;
+    mgr.save(&current);
+}
+
+#[tauri::command]
+fn set_confidence_filter(filter: ConfidenceFilter, options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) -> Result<(), String> {
+    settings::validate_confidence_filter(&filter).map_err(|e| e.to_string())?;
+    info!("Confidence filter set to: {:?}", filter);
+    options.0.write().unwrap().confidence_filter = filter;
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.confidence_filter = filter;
+    mgr.save(&current);
+    Ok(())
+}
+
+/// Replaces the noise filter rules. The list is applied in order.
+#[tauri::command]
+fn set_filter_rules(rules: Vec<FilterRule>, options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) -> Result<(), String> {
+    let text_filter = TextFilter::new(&rules).map_err(|e| format!("{:#}", e))?;
+    info!("Filter rules set ({} rules)", rules.len());
+    options.0.write().unwrap().text_filter = Arc::new(text_filter);
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.filter_rules = rules;
+    mgr.save(&current);
+    Ok(())
+}
+
+/// Restores the built-in filter rules and returns them.
+#[tauri::command]
+fn reset_filter_rules(options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) -> Vec<FilterRule> {
+    let rules = filter::default_rules();
+    options.0.write().unwrap().text_filter = Arc::new(TextFilter::default());
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.filter_rules = rules.clone();
+    mgr.save(&current);
+    rules
+}
+
+/// Switches to a decoding preset and returns its parameters. Applies from the next utterance.
+#[tauri::command]
+fn set_decoding_preset(preset: DecodingPreset, options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) -> Result<DecodingParams, String> {
+    let params = preset.params().ok_or("Custom is not a preset, set the parameters instead")?;
+    info!("Decoding preset set to: {:?}", preset);
+    options.0.write().unwrap().decoding = params;
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.decoding_preset = preset;
+    current.decoding = params;
+    mgr.save(&current);
+    Ok(params)
+}
+
+/// Sets hand-tuned decoding parameters. Applies from the next utterance.
+#[tauri::command]
+fn set_decoding(params: DecodingParams, options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) -> Result<(), String> {
+    settings::validate_decoding(&params).map_err(|e| e.to_string())?;
+    info!("Decoding parameters set to: {:?}", params);
+    options.0.write().unwrap().decoding = params;
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.decoding_preset = DecodingPreset::Custom;
+    current.decoding = params;
+    mgr.save(&current);
+    Ok(())
+}
+
+/// Points transcription at a server, or back at the local model. Applies from the next utterance.
+#[tauri::command]
+fn set_http_backend(config: HttpBackendConfig, options: tauri::State<'_, TranscriptionOptionsState>, app: tauri::AppHandle) -> Result<(), String> {
+    settings::validate_http_backend(&config).map_err(|e| e.to_string())?;
+    info!("Transcription server set to: {:?}", config);
+    options.0.write().unwrap().http_backend = config.clone();
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.http_backend = config;
+    mgr.save(&current);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_context_idle_reset(ms: u64, context: tauri::State<'_, ContextState>, app: tauri::AppHandle) -> Result<(), String> {
+    settings::validate_context_idle_reset(ms).map_err(|e| e.to_string())?;
+    info!("Context idle reset set to: {}ms", ms);
+    context.0.lock().unwrap().set_idle_reset(Duration::from_millis(ms));
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.context_idle_reset_ms = ms;
+    mgr.save(&current);
+    Ok(())
+}
+
+/// Sets the dictation language: a Whisper language code, or "auto" to detect it.
+#[tauri::command]
+fn set_language(
+    language: String,
+    options: tauri::State<'_, TranscriptionOptionsState>,
+    model_state: tauri::State<'_, ModelState>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let language = language.trim().to_lowercase();
+    let known = language == "auto"
+        || (language.chars().all(|c| c.is_ascii_alphabetic()) && whisper_rs::get_lang_id(&language).is_some());
+    if !known {
+        return Err(format!("Unknown language '{}'", language));
+    }
+    info!("Language set to: {}", language);
+    options.0.write().unwrap().language = language.clone();
+
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.language = language;
+    mgr.save(&current);
+
+    ensure_multilingual_model(app, &current, &model_state);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_translate(state: bool, options: tauri::State<'_, TranscriptionOptionsState>, model_state: tauri::State<'_, ModelState>, app: tauri::AppHandle) {
+    info!("Translate to English set to: {}", state);
+    options.0.write().unwrap().translate = state;
+
+    let mgr = settings::SettingsManager::new(&app);
+    let mut current = mgr.load();
+    current.translate = state;
+    mgr.save(&current);
+
+    ensure_multilingual_model(app, &current, &model_state);
+}
+
+/// Swaps an English-only model for its multilingual sibling when the settings need one.
+fn ensure_multilingual_model(app: AppHandle, current: &settings::AppSettings, model_state: &ModelState) {
+    let needed = model::model_for_language(&current.model, &current.language, current.translate);
+    if needed != current.model {
+        info!("'{}' is English-only. Switching to '{}'", current.model, needed);
+        switch_model(app, model_state, needed);
+    }
+}
+
 #[tauri::command]
 fn get_settings(app: tauri::AppHandle) -> settings::AppSettings {
     let mgr = settings::SettingsManager::new(&app);
     mgr.load()
 }
 
+#[tauri::command]
+fn get_engine_status(status: tauri::State<'_, EngineStatusState>) -> EngineStatusPayload {
+    status.0.read().unwrap().clone()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
@@ -332,12 +1367,44 @@ pub fn run() {
         set_silence_timeout, 
         set_allow_commands,
         set_disable_punctuation,
+        set_spoken_punctuation,
+        set_itn,
+        set_disfluency,
         upsert_shortcut,
         delete_shortcut,
+        upsert_glossary_term,
+        delete_glossary_term,
+        list_input_devices,
+        set_input_device,
+        set_channel_mix,
+        set_resample_quality,
+        set_prefer_native_rate,
+        set_vad_backend,
+        set_adaptive_vad,
+        set_max_utterance,
+        set_activation_mode,
+        set_activation_hotkey,
+        set_vad_thresholds,
+        set_vad_tuning,
+        reset_vad_tuning,
+        set_model,
+        set_language,
+        set_translate,
+        set_carry_context,
+        set_context_idle_reset,
+        set_confidence_filter,
+        set_filter_rules,
+        reset_filter_rules,
+        set_http_backend,
+        set_decoding_preset,
+        set_decoding,
+        calibrate_vad,
+        get_engine_status,
         get_settings
     ])
     .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, Some(vec!["--minimized"])))
     .plugin(tauri_plugin_log::Builder::default().build())
+    .plugin(tauri_plugin_global_shortcut::Builder::new().build())
     .setup(|app| {
         let handle = app.handle().clone();
         start_engine(handle)?;
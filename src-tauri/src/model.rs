@@ -6,6 +6,37 @@ use std::io::copy;
 use log::info;
 use tauri::{AppHandle, Manager};
 
+/// Whisper models that can be selected in settings.
+pub const AVAILABLE_MODELS: &[&str] = &[
+    "tiny.en",
+    "tiny",
+    "base.en",
+    "base",
+    "small.en",
+    "small",
+    "medium.en",
+    "medium",
+    "large-v3-turbo",
+    "distil-large-v3",
+];
+
+/// Multilingual stand-in for the distil models, which are English-only and have no
+/// multilingual version of their own.
+const DISTIL_MULTILINGUAL: &str = "large-v3-turbo";
+
+/// English-only models (`*.en`, `distil-*`) can't transcribe other languages or translate.
+/// Returns a multilingual model of similar size in that case, `model_name` otherwise.
+pub fn model_for_language(model_name: &str, language: &str, translate: bool) -> String {
+    if language == "en" && !translate {
+        return model_name.to_string();
+    }
+    match model_name.strip_suffix(".en") {
+        Some(multilingual) => multilingual.to_string(),
+        None if model_name.starts_with("distil-") => DISTIL_MULTILINGUAL.to_string(),
+        None => model_name.to_string(),
+    }
+}
+
 pub struct ModelManager {
     app: AppHandle,
 }
@@ -18,11 +49,32 @@ impl ModelManager {
     /// Returns the path to the requested model.
     /// Priority: 1. Bundled Resource, 2. Local File, 3. Download
     pub fn get_or_download_model(&self, model_name: &str) -> Result<PathBuf> {
+        if !AVAILABLE_MODELS.contains(&model_name) {
+            anyhow::bail!("Unknown Whisper model '{}'", model_name);
+        }
         let file_name = format!("ggml-{}.bin", model_name);
+        // Construct URL for HuggingFace. Distil models are published by distil-whisper,
+        // the rest by ggerganov/whisper.cpp.
+        let url = if model_name.starts_with("distil-") {
+            format!("https://huggingface.co/distil-whisper/{}-ggml/resolve/main/{}", model_name, file_name)
+        } else {
+            format!("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}", file_name)
+        };
+        self.get_or_download(&file_name, &url)
+    }
 
+    /// Returns the path to the Silero VAD model, same lookup order as Whisper models.
+    pub fn get_or_download_vad_model(&self) -> Result<PathBuf> {
+        self.get_or_download(
+            "silero_vad.onnx",
+            "https://github.com/snakers4/silero-vad/raw/master/src/silero_vad/data/silero_vad.onnx",
+        )
+    }
+
+    fn get_or_download(&self, file_name: &str, url: &str) -> Result<PathBuf> {
         // 1. Check Bundled Resources
         if let Ok(resource_dir) = self.app.path().resource_dir() {
-            let bundled_path = resource_dir.join("models").join(&file_name);
+            let bundled_path = resource_dir.join("models").join(file_name);
             if bundled_path.exists() {
                 info!("Using bundled model: {:?}", bundled_path);
                 return Ok(bundled_path);
@@ -38,43 +90,62 @@ impl ModelManager {
             fs::create_dir_all(&local_dir).context("Failed to create models directory")?;
         }
 
-        let local_path = local_dir.join(&file_name);
+        let local_path = local_dir.join(file_name);
         if local_path.exists() {
             info!("Using local model: {:?}", local_path);
             return Ok(local_path);
         }
 
         // 3. Download
-        info!("Model '{}' not found. Downloading...", model_name);
-        self.download_model(model_name, &local_path)?;
+        info!("Model '{}' not found. Downloading...", file_name);
+        self.download_model(url, &local_path)?;
         
         Ok(local_path)
     }
 
-    fn download_model(&self, name: &str, dest: &Path) -> Result<()> {
-        // Construct URL for HuggingFace (ggerganov/whisper.cpp)
-        // Note: distil models might be in a different repo, but let's stick to standard for now or provide full URL logic
-        let url = format!(
-            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{}.bin",
-            name
-        );
-
+    fn download_model(&self, url: &str, dest: &Path) -> Result<()> {
         info!("Downloading from: {}", url);
 
-        let mut response = reqwest::blocking::get(&url)
+        let mut response = reqwest::blocking::get(url)
             .context("Failed to send request to model URL")?;
         
         if !response.status().is_success() {
             anyhow::bail!("Failed to download model: Status {}", response.status());
         }
 
-        let mut dest_file = fs::File::create(dest)
-            .context("Failed to create model file")?;
+        // Download next to the destination and rename once complete, so an interrupted
+        // download is never mistaken for a finished model
+        let part_path = dest.with_extension("part");
+        {
+            let mut dest_file = fs::File::create(&part_path)
+                .context("Failed to create model file")?;
 
-        copy(&mut response, &mut dest_file)
-            .context("Failed to write model content to file")?;
+            copy(&mut response, &mut dest_file)
+                .context("Failed to write model content to file")?;
+        }
+        fs::rename(&part_path, dest).context("Failed to move downloaded model into place")?;
 
         info!("Download complete: {:?}", dest);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_for_language() {
+        assert_eq!(model_for_language("base.en", "en", false), "base.en");
+        assert_eq!(model_for_language("base.en", "de", false), "base");
+        assert_eq!(model_for_language("base.en", "en", true), "base");
+        assert_eq!(model_for_language("distil-large-v3", "en", false), "distil-large-v3");
+        assert_eq!(model_for_language("distil-large-v3", "de", false), "large-v3-turbo");
+        assert_eq!(model_for_language("distil-large-v3", "auto", false), "large-v3-turbo");
+        assert_eq!(model_for_language("distil-large-v3", "en", true), "large-v3-turbo");
+        assert_eq!(model_for_language("small", "fr", true), "small");
+        for model in AVAILABLE_MODELS {
+            assert!(AVAILABLE_MODELS.contains(&model_for_language(model, "de", false).as_str()));
+        }
+    }
+}
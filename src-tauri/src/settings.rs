@@ -1,18 +1,179 @@
+use anyhow::{ensure, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use tauri::AppHandle;
 use tauri::Manager;
-use log::{info, error};
+use log::{info, warn, error};
 use std::collections::HashMap;
 
+use crate::audio::downmix::ChannelMix;
+use crate::audio::resample::ResampleQuality;
+use crate::audio::segmenter::ActivationMode;
+use crate::audio::vad::{VadBackend, VadThresholds};
+use crate::text::disfluency::{self, DisfluencyConfig};
+use crate::text::itn::ItnOptions;
+use crate::transcription::confidence::ConfidenceFilter;
+use crate::transcription::decoding::{DecodingParams, DecodingPreset};
+use crate::transcription::filter::{self, FilterRule, TextFilter};
+use crate::transcription::http::HttpBackendConfig;
+use crate::transcription::prompt::GlossaryTerm;
+
+// Accepted ranges for the VAD tuning knobs
+pub const SILENCE_TIMEOUT_RANGE_MS: RangeInclusive<u64> = 100..=5000;
+pub const START_WINDOW_RANGE_MS: RangeInclusive<u64> = 30..=2000;
+pub const PRE_ROLL_RANGE_MS: RangeInclusive<u64> = 0..=2000;
+pub const POST_ROLL_RANGE_MS: RangeInclusive<u64> = 0..=1000;
+pub const START_THRESHOLD_RANGE: RangeInclusive<f32> = 0.0005..=0.5;
+pub const STOP_THRESHOLD_RANGE: RangeInclusive<f32> = 0.0001..=0.5;
+/// Accepted length cap for one utterance; 0 turns the cap off
+pub const MAX_UTTERANCE_RANGE_MS: RangeInclusive<u64> = 5000..=120_000;
+/// Accepted idle time before carried-over transcript context is dropped
+pub const CONTEXT_IDLE_RESET_RANGE_MS: RangeInclusive<u64> = 1000..=600_000;
+/// Accepted range for the confidence filter's probabilities
+pub const PROBABILITY_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+/// Accepted request timeout for the transcription server
+pub const HTTP_TIMEOUT_RANGE_MS: RangeInclusive<u64> = 500..=60_000;
+// Accepted ranges for the Whisper decoding parameters
+pub const DECODING_THREADS_RANGE: RangeInclusive<u32> = 0..=64;
+/// whisper.cpp runs at most 8 decoders
+pub const BEAM_SIZE_RANGE: RangeInclusive<u32> = 1..=8;
+pub const TEMPERATURE_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+/// Half the text context, the most a segment can hold
+pub const MAX_TOKENS_RANGE: RangeInclusive<u32> = 0..=224;
+
+/// Timing knobs for voice detection and the audio kept around an utterance.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VadTuning {
+    /// Sustained speech needed before an utterance opens.
+    pub start_window_ms: u64,
+    /// Audio kept from just before speech was detected.
+    pub pre_roll_ms: u64,
+    /// Audio kept after speech ended, so trailing sounds aren't clipped.
+    pub post_roll_ms: u64,
+}
+
+impl Default for VadTuning {
+    fn default() -> Self {
+        Self { start_window_ms: 300, pre_roll_ms: 500, post_roll_ms: 0 }
+    }
+}
+
+impl VadTuning {
+    pub fn validate(&self) -> Result<()> {
+        check_range("Start window", self.start_window_ms, START_WINDOW_RANGE_MS)?;
+        check_range("Pre-roll", self.pre_roll_ms, PRE_ROLL_RANGE_MS)?;
+        check_range("Post-roll", self.post_roll_ms, POST_ROLL_RANGE_MS)?;
+        Ok(())
+    }
+}
+
+pub fn validate_silence_timeout(ms: u64) -> Result<()> {
+    check_range("Silence timeout", ms, SILENCE_TIMEOUT_RANGE_MS)
+}
+
+pub fn validate_max_utterance(ms: u64) -> Result<()> {
+    if ms == 0 {
+        return Ok(());
+    }
+    check_range("Max utterance length", ms, MAX_UTTERANCE_RANGE_MS)
+}
+
+pub fn validate_context_idle_reset(ms: u64) -> Result<()> {
+    check_range("Context idle reset", ms, CONTEXT_IDLE_RESET_RANGE_MS)
+}
+
+pub fn validate_confidence_filter(filter: &ConfidenceFilter) -> Result<()> {
+    check_range("Minimum confidence", filter.min_probability, PROBABILITY_RANGE)?;
+    check_range("Maximum no-speech probability", filter.max_no_speech_probability, PROBABILITY_RANGE)
+}
+
+pub fn validate_filter_rules(rules: &[FilterRule]) -> Result<()> {
+    TextFilter::new(rules).map(|_| ())
+}
+
+pub fn validate_decoding(params: &DecodingParams) -> Result<()> {
+    check_range("Threads", params.threads, DECODING_THREADS_RANGE)?;
+    check_range("Beam size", params.beam_size, BEAM_SIZE_RANGE)?;
+    check_range("Temperature", params.temperature, TEMPERATURE_RANGE)?;
+    check_range("Temperature increment", params.temperature_increment, TEMPERATURE_RANGE)?;
+    check_range("Max tokens", params.max_tokens, MAX_TOKENS_RANGE)
+}
+
+pub fn validate_http_backend(config: &HttpBackendConfig) -> Result<()> {
+    check_range("Server timeout", config.timeout_ms, HTTP_TIMEOUT_RANGE_MS)?;
+    let url = reqwest::Url::parse(&config.url).map_err(|e| anyhow::anyhow!("Invalid server URL '{}': {}", config.url, e))?;
+    ensure!(matches!(url.scheme(), "http" | "https"), "Server URL must start with http:// or https://");
+    Ok(())
+}
+
+pub fn validate_thresholds(start_threshold: f32, stop_threshold: f32) -> Result<()> {
+    check_range("Start threshold", start_threshold, START_THRESHOLD_RANGE)?;
+    check_range("Stop threshold", stop_threshold, STOP_THRESHOLD_RANGE)?;
+    ensure!(stop_threshold <= start_threshold, "Stop threshold must not exceed the start threshold");
+    Ok(())
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(name: &str, value: T, range: RangeInclusive<T>) -> Result<()> {
+    ensure!(range.contains(&value), "{} must be between {} and {} (got {})", name, range.start(), range.end(), value);
+    Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct AppSettings {
     pub auto_space: bool,
     pub silence_timeout: u64,
     pub allow_commands: bool,
     pub disable_punctuation: bool,
+    /// Dictated "comma", "period", "new line", ... anywhere in a sentence become the marks.
+    pub spoken_punctuation: bool,
     pub shortcuts: HashMap<String, String>,
+    /// Preferred input device name. `None` follows the system default.
+    pub input_device: Option<String>,
+    /// How multi-channel input is folded to mono.
+    pub channel_mix: ChannelMix,
+    pub resample_quality: ResampleQuality,
+    /// Ask the device for 16kHz directly when it supports it, skipping resampling.
+    pub prefer_native_rate: bool,
+    pub vad_backend: VadBackend,
+    /// Move the thresholds with the noise floor during silence.
+    pub adaptive_vad: bool,
+    /// Last calibration result, or the built-in defaults.
+    pub vad_thresholds: VadThresholds,
+    /// Longer utterances are split and transcribed while you keep talking. 0 = unlimited.
+    pub max_utterance_ms: u64,
+    pub activation_mode: ActivationMode,
+    /// Global hotkey for push-to-talk / toggle, e.g. "CommandOrControl+Shift+Space".
+    pub activation_hotkey: String,
+    pub vad_tuning: VadTuning,
+    /// Whisper model name, e.g. "tiny.en" or "small.en".
+    pub model: String,
+    /// Whisper language code, or "auto" to detect it.
+    pub language: String,
+    /// Translate speech into English.
+    pub translate: bool,
+    /// Terms fed to Whisper's initial prompt so they are spelled as written.
+    pub glossary: Vec<GlossaryTerm>,
+    /// Prompt each utterance with the end of the previous transcript.
+    pub carry_context: bool,
+    /// Silence after which the carried-over context is dropped.
+    pub context_idle_reset_ms: u64,
+    /// Drops transcripts Whisper wasn't confident about.
+    pub confidence_filter: ConfidenceFilter,
+    /// Preset the decoding parameters came from, or `Custom`.
+    pub decoding_preset: DecodingPreset,
+    pub decoding: DecodingParams,
+    /// Noise and hallucination rules, applied in order to every transcript.
+    pub filter_rules: Vec<FilterRule>,
+    /// Transcription server to use instead of the local model.
+    pub http_backend: HttpBackendConfig,
+    /// Which spoken numbers, dates, times and amounts are written out. English only.
+    pub itn: ItnOptions,
+    /// Filler and false-start cleanup.
+    pub disfluency: DisfluencyConfig,
 }
 
 impl Default for AppSettings {
@@ -30,7 +191,81 @@ impl Default for AppSettings {
             silence_timeout: 500,
             allow_commands: true,
             disable_punctuation: false,
+            spoken_punctuation: false,
             shortcuts,
+            input_device: None,
+            channel_mix: ChannelMix::default(),
+            resample_quality: ResampleQuality::default(),
+            prefer_native_rate: false,
+            vad_backend: VadBackend::default(),
+            adaptive_vad: false,
+            vad_thresholds: VadThresholds::default(),
+            max_utterance_ms: 15000,
+            activation_mode: ActivationMode::default(),
+            activation_hotkey: "CommandOrControl+Shift+Space".to_string(),
+            vad_tuning: VadTuning::default(),
+            model: "tiny.en".to_string(),
+            language: "en".to_string(),
+            translate: false,
+            glossary: Vec::new(),
+            carry_context: false,
+            context_idle_reset_ms: 30_000,
+            confidence_filter: ConfidenceFilter::default(),
+            decoding_preset: DecodingPreset::default(),
+            decoding: DecodingParams::default(),
+            filter_rules: filter::default_rules(),
+            http_backend: HttpBackendConfig::default(),
+            itn: ItnOptions::default(),
+            disfluency: DisfluencyConfig::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Replaces hand-edited values that are out of range with the defaults, and
+    /// clamps the max utterance length.
+    fn sanitize(&mut self) {
+        let defaults = AppSettings::default();
+        if let Err(e) = validate_silence_timeout(self.silence_timeout) {
+            warn!("{}. Using default.", e);
+            self.silence_timeout = defaults.silence_timeout;
+        }
+        if let Err(e) = validate_thresholds(self.vad_thresholds.start_threshold, self.vad_thresholds.stop_threshold) {
+            warn!("{}. Using defaults.", e);
+            self.vad_thresholds = defaults.vad_thresholds;
+        }
+        if let Err(e) = self.vad_tuning.validate() {
+            warn!("{}. Using defaults.", e);
+            self.vad_tuning = defaults.vad_tuning;
+        }
+        if let Err(e) = validate_max_utterance(self.max_utterance_ms) {
+            warn!("{}. Clamping.", e);
+            self.max_utterance_ms = self.max_utterance_ms.clamp(*MAX_UTTERANCE_RANGE_MS.start(), *MAX_UTTERANCE_RANGE_MS.end());
+        }
+        if let Err(e) = validate_context_idle_reset(self.context_idle_reset_ms) {
+            warn!("{}. Using default.", e);
+            self.context_idle_reset_ms = defaults.context_idle_reset_ms;
+        }
+        if let Err(e) = validate_confidence_filter(&self.confidence_filter) {
+            warn!("{}. Using defaults.", e);
+            self.confidence_filter = defaults.confidence_filter;
+        }
+        if let Err(e) = validate_decoding(&self.decoding) {
+            warn!("{}. Using defaults.", e);
+            self.decoding_preset = defaults.decoding_preset;
+            self.decoding = defaults.decoding;
+        }
+        if let Err(e) = validate_filter_rules(&self.filter_rules) {
+            warn!("{}. Using default filter rules.", e);
+            self.filter_rules = defaults.filter_rules;
+        }
+        if let Err(e) = validate_http_backend(&self.http_backend) {
+            warn!("{}. Using defaults.", e);
+            self.http_backend = defaults.http_backend;
+        }
+        if let Err(e) = disfluency::validate_fillers(&self.disfluency.fillers) {
+            warn!("{}. Using default fillers.", e);
+            self.disfluency.fillers = disfluency::default_fillers();
         }
     }
 }
@@ -52,9 +287,10 @@ impl SettingsManager {
     pub fn load(&self) -> AppSettings {
         if self.file_path.exists() {
             match fs::read_to_string(&self.file_path) {
-                Ok(content) => match serde_json::from_str(&content) {
-                    Ok(settings) => {
+                Ok(content) => match serde_json::from_str::<AppSettings>(&content) {
+                    Ok(mut settings) => {
                         info!("Settings loaded from {:?}", self.file_path);
+                        settings.sanitize();
                         return settings;
                     },
                     Err(e) => error!("Failed to parse settings: {}", e),
@@ -81,3 +317,64 @@ impl SettingsManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::disfluency::DisfluencyMode;
+
+    #[test]
+    fn test_vad_validation() {
+        assert!(VadTuning::default().validate().is_ok());
+        assert!(VadTuning { start_window_ms: 10, ..Default::default() }.validate().is_err());
+        assert!(VadTuning { post_roll_ms: 5000, ..Default::default() }.validate().is_err());
+
+        assert!(validate_thresholds(0.008, 0.005).is_ok());
+        assert!(validate_thresholds(0.005, 0.008).is_err());
+        assert!(validate_thresholds(f32::NAN, 0.005).is_err());
+        assert!(validate_silence_timeout(50).is_err());
+    }
+
+    #[test]
+    fn test_decoding_validation() {
+        for preset in [DecodingPreset::Fastest, DecodingPreset::Balanced, DecodingPreset::Accurate] {
+            assert!(validate_decoding(&preset.params().unwrap()).is_ok());
+        }
+        assert!(validate_decoding(&DecodingParams { beam_size: 0, ..Default::default() }).is_err());
+        assert!(validate_decoding(&DecodingParams { temperature: 1.5, ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn test_http_backend_validation() {
+        assert!(validate_http_backend(&HttpBackendConfig::default()).is_ok());
+        assert!(validate_http_backend(&HttpBackendConfig { url: "ftp://nas.local".to_string(), ..Default::default() }).is_err());
+        assert!(validate_http_backend(&HttpBackendConfig { url: "192.168.1.20:8000".to_string(), ..Default::default() }).is_err());
+        assert!(validate_http_backend(&HttpBackendConfig { timeout_ms: 0, ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn test_max_utterance_validation() {
+        assert!(validate_max_utterance(0).is_ok());
+        assert!(validate_max_utterance(15_000).is_ok());
+        assert!(validate_max_utterance(1).is_err());
+        assert!(validate_max_utterance(600_000).is_err());
+
+        let mut settings: AppSettings = serde_json::from_str(r#"{ "max_utterance_ms": 1 }"#).unwrap();
+        settings.sanitize();
+        assert_eq!(settings.max_utterance_ms, 5000);
+    }
+
+    #[test]
+    fn test_out_of_range_values_fall_back_to_defaults() {
+        let mut settings: AppSettings = serde_json::from_str(
+            r#"{ "silence_timeout": 99999, "vad_tuning": { "pre_roll_ms": 100 }, "vad_thresholds": { "noise_floor": 0.0, "start_threshold": 0.001, "stop_threshold": 0.01 }, "context_idle_reset_ms": 5, "filter_rules": [{ "kind": "regex", "pattern": "[", "action": "drop" }], "disfluency": { "mode": "aggressive", "fillers": ["um", ""] } }"#,
+        ).unwrap();
+        settings.sanitize();
+        assert_eq!(settings.silence_timeout, 500);
+        assert_eq!(settings.vad_tuning.pre_roll_ms, 100);
+        assert_eq!(settings.vad_thresholds, VadThresholds::default());
+        assert_eq!(settings.context_idle_reset_ms, 30_000);
+        assert_eq!(settings.filter_rules, filter::default_rules());
+        assert_eq!(settings.disfluency, DisfluencyConfig { mode: DisfluencyMode::Aggressive, ..Default::default() });
+    }
+}
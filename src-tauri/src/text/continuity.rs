@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Characters that attach to the text before them, so no space goes in front.
+const ATTACHING: &[char] = &[',', '.', ';', ':', '!', '?', ')', ']', '}', '%', '\n'];
+/// Marks that end a sentence.
+const SENTENCE_ENDS: &[char] = &['.', '?', '!', '\n'];
+/// Closing characters that may follow a sentence's final mark: `done."`
+const CLOSING: &[char] = &['"', '\'', ')', ']', '}', '”', '’'];
+/// Cap on remembered proper nouns, so a long session doesn't grow without bound.
+const MAX_PROPER_NOUNS: usize = 1000;
+
+/// What the last utterance typed into a window ended with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Ending {
+    sentence: bool,
+    space: bool,
+}
+
+/// Joins consecutive utterances typed into the same window as one piece of text.
+///
+/// Whisper transcribes every utterance as if it started a sentence. When the previous
+/// utterance didn't end one, the next starts lowercase ("and then" rather than "And
+/// then"); when it did, the next starts uppercase. A space goes between the two only
+/// when neither side provides one. Focus changes, commands and long pauses start over,
+/// since what precedes the cursor is no longer known. Where the focused window can't be
+/// told (macOS), only the pause does.
+///
+/// Words Whisper capitalizes mid-sentence are taken for names and keep their capital
+/// when they start a continuing utterance ("John said", not "john said").
+#[derive(Debug)]
+pub struct Continuity {
+    window: Option<u64>,
+    ending: Option<Ending>,
+    updated: Option<Instant>,
+    idle_reset: Duration,
+    proper_nouns: HashSet<String>,
+}
+
+impl Continuity {
+    pub fn new(idle_reset: Duration) -> Self {
+        Self { window: None, ending: None, updated: None, idle_reset, proper_nouns: HashSet::new() }
+    }
+
+    pub fn set_idle_reset(&mut self, idle_reset: Duration) {
+        self.idle_reset = idle_reset;
+    }
+
+    pub fn reset(&mut self) {
+        self.ending = None;
+    }
+
+    /// Adjusts `text` to follow what was typed before into `window`, and remembers it.
+    pub fn apply(&mut self, text: &str, window: Option<u64>, auto_space: bool, now: Instant) -> String {
+        let idle = self.updated.is_some_and(|updated| now.saturating_duration_since(updated) > self.idle_reset);
+        if window != self.window || idle {
+            self.window = window;
+            self.reset();
+        }
+        let body = text.trim_start_matches(' ');
+        if body.is_empty() {
+            return text.to_string();
+        }
+        self.updated = Some(now);
+        self.learn_proper_nouns(body);
+
+        let output = match self.ending {
+            None => text.to_string(),
+            Some(ending) => {
+                let attaches = body.starts_with(ATTACHING);
+                let space = if auto_space && !ending.space && !attaches { " " } else { "" };
+                let body = if ending.sentence { capitalize(body) } else { self.lowercase_first_word(body) };
+                format!("{}{}", space, body)
+            }
+        };
+        let end = output.trim_end_matches(CLOSING);
+        self.ending = Some(Ending {
+            sentence: end.ends_with(SENTENCE_ENDS),
+            space: output.ends_with(char::is_whitespace),
+        });
+        output
+    }
+
+    /// Remembers words capitalized anywhere but at the start of a sentence.
+    fn learn_proper_nouns(&mut self, text: &str) {
+        for line in text.lines() {
+            let mut sentence_start = true;
+            for raw in line.split_whitespace() {
+                let word = first_word(raw);
+                let capitalized = word.starts_with(char::is_uppercase) && word != "I";
+                if capitalized && !sentence_start && self.proper_nouns.len() < MAX_PROPER_NOUNS {
+                    self.proper_nouns.insert(word.to_string());
+                }
+                sentence_start = raw.trim_end_matches(CLOSING).ends_with(SENTENCE_ENDS);
+            }
+        }
+    }
+
+    /// Lowercases a first word that is only capitalized for starting the utterance.
+    /// "I", "I'm", words like "NASA" or "FlowType", and words Whisper has capitalized
+    /// mid-sentence keep their casing.
+    fn lowercase_first_word(&self, text: &str) -> String {
+        let word = text.split(|c: char| !c.is_alphabetic()).next().unwrap_or("");
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else { return text.to_string() };
+        let pronoun = word == "I";
+        if !first.is_uppercase() || pronoun || chars.any(char::is_uppercase) || self.proper_nouns.contains(word) {
+            return text.to_string();
+        }
+        first.to_lowercase().chain(text.chars().skip(1)).collect()
+    }
+}
+
+/// The first run of letters in a word: "I" for "I'm", "Okafor" for "(Okafor,".
+fn first_word(text: &str) -> &str {
+    text.trim_start_matches(|c: char| !c.is_alphabetic()).split(|c: char| !c.is_alphabetic()).next().unwrap_or("")
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDITOR: Option<u64> = Some(1);
+    const CHAT: Option<u64> = Some(2);
+    const IDLE_RESET: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_utterances_join_into_sentences() {
+        let mut continuity = Continuity::new(IDLE_RESET);
+        let now = Instant::now();
+        let cases = [
+            // First utterance in a window is typed as is
+            ("I went to the store", "I went to the store"),
+            ("And then I left.", " and then I left."),
+            ("after that", " After that"),
+            (", which was fine", ", which was fine"),
+            ("I'm home", " I'm home"),
+            ("NASA called?", " NASA called?"),
+            ("Really.\n", " Really.\n"),
+            ("Next line", "Next line"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(continuity.apply(input, EDITOR, true, now), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_focus_change_and_reset_start_over() {
+        let mut continuity = Continuity::new(IDLE_RESET);
+        let now = Instant::now();
+        continuity.apply("Dear team", EDITOR, true, now);
+        assert_eq!(continuity.apply("Hello", CHAT, true, now), "Hello");
+        assert_eq!(continuity.apply("Thanks", EDITOR, true, now), "Thanks");
+        continuity.reset();
+        assert_eq!(continuity.apply("Bye", EDITOR, true, now), "Bye");
+
+        // Without auto-space only the casing changes
+        assert_eq!(continuity.apply("For now.", EDITOR, false, now), "for now.");
+        assert_eq!(continuity.apply("\"quoted.\"", EDITOR, false, now), "\"quoted.\"");
+        assert_eq!(continuity.apply("next", EDITOR, false, now), "Next");
+
+        // A long pause starts over too, the only reset where windows can't be told apart
+        assert_eq!(continuity.apply("And more", None, true, now), "And more");
+        assert_eq!(continuity.apply("Later", None, true, now + IDLE_RESET * 2), "Later");
+    }
+
+    #[test]
+    fn test_names_keep_their_capital() {
+        let mut continuity = Continuity::new(IDLE_RESET);
+        let now = Instant::now();
+        assert_eq!(continuity.apply("I talked to John and Anya Okafor", EDITOR, true, now), "I talked to John and Anya Okafor");
+        assert_eq!(continuity.apply("John said yes", EDITOR, true, now), " John said yes");
+        assert_eq!(continuity.apply("Okafor agreed", EDITOR, true, now), " Okafor agreed");
+        assert_eq!(continuity.apply("Then we left", EDITOR, true, now), " then we left");
+        // Capitalized only for starting a sentence or a line: not taken for a name
+        assert_eq!(continuity.apply("Done. Then\nAnd so", EDITOR, true, now), " done. Then\nAnd so");
+        assert_eq!(continuity.apply("And", EDITOR, true, now), " and");
+    }
+}
@@ -0,0 +1,282 @@
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use super::pipeline::TextProcessor;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisfluencyMode {
+    #[default]
+    Off,
+    /// Drops fillers only.
+    Conservative,
+    /// Also collapses repeats ("the the") and self-corrections ("at five, no, six").
+    Aggressive,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisfluencyConfig {
+    pub mode: DisfluencyMode,
+    /// Single words are dropped wherever they appear as a word. Phrases ("you know")
+    /// only when set off by punctuation, as they are often meant literally.
+    pub fillers: Vec<String>,
+}
+
+impl Default for DisfluencyConfig {
+    fn default() -> Self {
+        Self { mode: DisfluencyMode::default(), fillers: default_fillers() }
+    }
+}
+
+pub fn default_fillers() -> Vec<String> {
+    ["um", "umm", "uh", "uhm", "er", "erm", "hmm", "mm", "you know", "I mean"]
+        .iter()
+        .map(|filler| filler.to_string())
+        .collect()
+}
+
+pub fn validate_fillers(fillers: &[String]) -> Result<()> {
+    for filler in fillers {
+        ensure!(!filler.trim().is_empty(), "Fillers can't be empty");
+        ensure!(filler.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '\''), "Filler '{}' contains punctuation", filler);
+    }
+    Ok(())
+}
+
+/// Phrases that introduce a correction of the words before them, when set off by commas.
+const CORRECTION_MARKERS: &[&[&str]] = &[&["no"], &["i", "mean"], &["or", "rather"]];
+/// Doubled words that are usually meant: "I know that that works", digits read out.
+const INTENDED_REPEATS: &[&str] = &["that", "had", "bye", "oh", "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+/// Longest repeated phrase collapsed, in words.
+const MAX_REPEAT_WORDS: usize = 3;
+
+/// A word with the punctuation around it split off.
+#[derive(Debug, Clone)]
+struct Word {
+    lead: String,
+    text: String,
+    trail: String,
+}
+
+impl Word {
+    fn parse(raw: &str) -> Self {
+        let start = raw.find(|c: char| c.is_alphanumeric()).unwrap_or(raw.len());
+        let end = raw.rfind(|c: char| c.is_alphanumeric()).map_or(start, |i| i + raw[i..].chars().next().unwrap().len_utf8());
+        Self { lead: raw[..start].to_string(), text: raw[start..end].to_string(), trail: raw[end..].to_string() }
+    }
+
+    fn is(&self, word: &str) -> bool {
+        !self.text.is_empty() && self.text.eq_ignore_ascii_case(word)
+    }
+
+    fn ends_sentence(&self) -> bool {
+        self.trail.contains(['.', '?', '!'])
+    }
+}
+
+/// Cleans up hesitations Whisper transcribes faithfully: fillers, and in aggressive
+/// mode stuttered repeats and corrected false starts. Relies on Whisper's commas, so
+/// it runs before punctuation is stripped.
+pub struct DisfluencyCleaner {
+    mode: DisfluencyMode,
+    fillers: Vec<Vec<String>>,
+}
+
+impl DisfluencyCleaner {
+    pub fn new(config: &DisfluencyConfig) -> Self {
+        let fillers = config
+            .fillers
+            .iter()
+            .map(|filler| filler.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>())
+            .filter(|words| !words.is_empty())
+            .collect();
+        Self { mode: config.mode, fillers }
+    }
+}
+
+impl TextProcessor for DisfluencyCleaner {
+    fn name(&self) -> &'static str {
+        "disfluency cleanup"
+    }
+
+    fn process(&mut self, text: &str) -> String {
+        if self.mode == DisfluencyMode::Off {
+            return text.to_string();
+        }
+        let mut words: Vec<Word> = text.split_whitespace().map(Word::parse).collect();
+        let count = words.len();
+        if self.mode == DisfluencyMode::Aggressive {
+            remove_corrections(&mut words);
+        }
+        remove_fillers(&mut words, &self.fillers);
+        if self.mode == DisfluencyMode::Aggressive {
+            remove_repeats(&mut words);
+        }
+        if words.len() == count {
+            return text.to_string();
+        }
+        words.iter().map(|w| format!("{}{}{}", w.lead, w.text, w.trail)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Whether `phrase` starts at word `i`, with no punctuation inside it.
+fn phrase_at(words: &[Word], i: usize, phrase: &[impl AsRef<str>]) -> bool {
+    let Some(span) = words.get(i..i + phrase.len()) else { return false };
+    span.iter().zip(phrase).enumerate().all(|(k, (word, expected))| {
+        word.is(expected.as_ref()) && (k == 0 || word.lead.is_empty()) && (k + 1 == phrase.len() || word.trail.is_empty())
+    })
+}
+
+/// Removes `words[start..end]`, handing sentence-ending punctuation to the word before
+/// and the sentence's capital to the word after.
+fn remove(words: &mut Vec<Word>, start: usize, end: usize) {
+    let first = words[start].clone();
+    let last_trail = words[end - 1].trail.clone();
+    let sentence_start = start == 0 || words[start - 1].ends_sentence();
+    if start > 0 && last_trail.contains(['.', '?', '!']) && !words[start - 1].ends_sentence() {
+        words[start - 1].trail = last_trail.trim_start_matches(',').to_string();
+    }
+    if let Some(next) = words.get_mut(end) {
+        next.lead.insert_str(0, &first.lead);
+        let capitalized = first.text.starts_with(char::is_uppercase) && first.text != "I" && !first.text.starts_with("I'");
+        if sentence_start && capitalized {
+            let mut chars = next.text.chars();
+            if let Some(c) = chars.next() {
+                next.text = c.to_uppercase().chain(chars).collect();
+            }
+        }
+    }
+    words.drain(start..end);
+}
+
+fn remove_fillers(words: &mut Vec<Word>, fillers: &[Vec<String>]) {
+    let mut i = 0;
+    while i < words.len() {
+        let filler = fillers.iter().find(|filler| {
+            if !phrase_at(words, i, filler) {
+                return false;
+            }
+            let end = i + filler.len();
+            let set_off = (i == 0 || !words[i - 1].trail.is_empty()) && (end == words.len() || !words[end - 1].trail.is_empty());
+            filler.len() == 1 || set_off
+        });
+        match filler {
+            Some(filler) => remove(words, i, i + filler.len()),
+            None => i += 1,
+        }
+    }
+}
+
+/// "at five, no, six" -> "at six": the marker and the word before it. When the
+/// correction restates the clause ("to John, no, to Mary"), from its first word on.
+fn remove_corrections(words: &mut Vec<Word>) {
+    let mut i = 1;
+    while i < words.len() {
+        let marker = CORRECTION_MARKERS.iter().find(|marker| {
+            words[i - 1].trail == "," && phrase_at(words, i, marker) && words[i + marker.len() - 1].trail == ","
+        });
+        let Some(marker) = marker else {
+            i += 1;
+            continue;
+        };
+        let repair = i + marker.len();
+        if repair == words.len() {
+            break;
+        }
+        let clause_start = words[..i - 1].iter().rposition(|w| !w.trail.is_empty()).map_or(0, |p| p + 1);
+        let start = (clause_start..i).find(|&p| words[p].is(&words[repair].text)).unwrap_or(i - 1);
+        remove(words, start, repair);
+        i = start + 1;
+    }
+}
+
+/// "the the" -> "the", "I think I think" -> "I think".
+fn remove_repeats(words: &mut Vec<Word>) {
+    let mut i = 0;
+    while i < words.len() {
+        let repeated = (1..=MAX_REPEAT_WORDS).find(|&n| {
+            let Some(second) = words.get(i + n..i + 2 * n) else { return false };
+            let first = &words[i..i + n];
+            let intended = n == 1 && INTENDED_REPEATS.iter().any(|w| first[0].is(w));
+            let numeric = first.iter().any(|w| w.text.contains(|c: char| c.is_ascii_digit()));
+            // A stutter may carry a comma ("the, the"), nothing stronger
+            let joined = first.iter().enumerate().all(|(k, w)| w.trail.is_empty() || (k + 1 == n && w.trail == ","))
+                && second[0].lead.is_empty();
+            !intended && !numeric && joined && first.iter().zip(second).all(|(a, b)| !a.text.is_empty() && a.is(&b.text))
+        });
+        match repeated {
+            Some(n) => remove(words, i, i + n),
+            None => i += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean(mode: DisfluencyMode, text: &str) -> String {
+        DisfluencyCleaner::new(&DisfluencyConfig { mode, ..Default::default() }).process(text)
+    }
+
+    #[test]
+    fn test_conservative_drops_fillers_only() {
+        let cases = [
+            ("Um, I think we should go.", "I think we should go."),
+            ("So uh we left", "So we left"),
+            ("We should, um.", "We should."),
+            ("Well, you know, it works.", "Well, it works."),
+            // Fillers inside words and phrases meant literally stay
+            ("Do you know the umbrella man?", "Do you know the umbrella man?"),
+            ("I mean it.", "I mean it."),
+            // Repeats are left for aggressive mode
+            ("the the cat", "the the cat"),
+            ("Um.", ""),
+            // Shorter than a phrase filler
+            ("Hello.", "Hello."),
+            ("Thanks, bye.", "Thanks, bye."),
+            ("Yes, no, maybe.", "Yes, no, maybe."),
+            ("So, you know", "So,"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(clean(DisfluencyMode::Conservative, input), expected, "input: {:?}", input);
+        }
+        assert_eq!(clean(DisfluencyMode::Off, "Um, hi."), "Um, hi.");
+    }
+
+    #[test]
+    fn test_aggressive_collapses_repeats_and_corrections() {
+        let cases = [
+            ("Meet me at five, no, six.", "Meet me at six."),
+            ("at five, no, six p.m.", "at six p.m."),
+            ("Send it to John, no, to Mary.", "Send it to Mary."),
+            ("On Monday, I mean, Tuesday, we ship.", "On Tuesday, we ship."),
+            ("Tuesday, or rather, Wednesday works", "Wednesday works"),
+            ("the the cat", "the cat"),
+            ("The, the cat", "The cat"),
+            ("I think I think it's fine", "I think it's fine"),
+            ("Um, the the plan", "The plan"),
+            // Doubles that are meant
+            ("I know that that works", "I know that that works"),
+            ("Call five five five", "Call five five five"),
+            ("Is it ready? No, not yet.", "Is it ready? No, not yet."),
+            ("Hello.", "Hello."),
+            ("Thanks, bye.", "Thanks, bye."),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(clean(DisfluencyMode::Aggressive, input), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_filler_list_is_configurable() {
+        let config = DisfluencyConfig { mode: DisfluencyMode::Conservative, fillers: vec!["like".to_string(), "sort of".to_string()] };
+        let mut cleaner = DisfluencyCleaner::new(&config);
+        assert_eq!(cleaner.process("Well, sort of, like fine um"), "Well, fine um");
+
+        assert!(validate_fillers(&default_fillers()).is_ok());
+        assert!(validate_fillers(&[" ".to_string()]).is_err());
+        assert!(validate_fillers(&["uh,".to_string()]).is_err());
+    }
+}
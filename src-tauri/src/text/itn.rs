@@ -0,0 +1,821 @@
+use serde::{Deserialize, Serialize};
+
+use super::pipeline::TextProcessor;
+
+/// Which kinds of spoken forms are rewritten. English only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItnOptions {
+    /// "twenty three" -> "23". One to nine stay words, as in running text.
+    pub numbers: bool,
+    /// "twenty first" -> "21st"
+    pub ordinals: bool,
+    /// "five percent" -> "5%"
+    pub percentages: bool,
+    /// "five thirty pm" -> "5:30 PM"
+    pub times: bool,
+    /// "march fifth twenty twenty four" -> "March 5, 2024", "nineteen ninety" -> "1990"
+    pub dates: bool,
+    /// "ten dollars and fifty cents" -> "$10.50"
+    pub currency: bool,
+    /// "five kilometers" -> "5 km"
+    pub units: bool,
+}
+
+impl Default for ItnOptions {
+    fn default() -> Self {
+        Self { numbers: true, ordinals: true, percentages: true, times: true, dates: true, currency: true, units: true }
+    }
+}
+
+impl ItnOptions {
+    pub fn any(&self) -> bool {
+        self.numbers || self.ordinals || self.percentages || self.times || self.dates || self.currency || self.units
+    }
+}
+
+/// Inverse text normalization: turns spoken numbers, dates, times, amounts and
+/// measurements into their written form.
+pub struct InverseNormalizer {
+    options: ItnOptions,
+}
+
+impl InverseNormalizer {
+    pub fn new(options: ItnOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl TextProcessor for InverseNormalizer {
+    fn name(&self) -> &'static str {
+        "inverse text normalization"
+    }
+
+    fn process(&mut self, text: &str) -> String {
+        normalize(text, &self.options)
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Longest phrases first, so "degrees celsius" wins over "degrees".
+const UNIT_SYMBOLS: &[(&[&str], &str)] = &[
+    (&["kilometers", "per", "hour"], "km/h"),
+    (&["kilometres", "per", "hour"], "km/h"),
+    (&["miles", "per", "hour"], "mph"),
+    (&["degrees", "celsius"], "°C"),
+    (&["degrees", "fahrenheit"], "°F"),
+    (&["degree", "celsius"], "°C"),
+    (&["degree", "fahrenheit"], "°F"),
+    (&["degrees"], "°"),
+    (&["degree"], "°"),
+    (&["kilometers"], "km"),
+    (&["kilometres"], "km"),
+    (&["kilometer"], "km"),
+    (&["kilometre"], "km"),
+    (&["meters"], "m"),
+    (&["metres"], "m"),
+    (&["meter"], "m"),
+    (&["metre"], "m"),
+    (&["centimeters"], "cm"),
+    (&["centimetres"], "cm"),
+    (&["millimeters"], "mm"),
+    (&["millimetres"], "mm"),
+    (&["kilograms"], "kg"),
+    (&["kilogram"], "kg"),
+    (&["kilos"], "kg"),
+    (&["grams"], "g"),
+    (&["gram"], "g"),
+    (&["liters"], "L"),
+    (&["litres"], "L"),
+    (&["liter"], "L"),
+    (&["litre"], "L"),
+    (&["milliliters"], "ml"),
+    (&["millilitres"], "ml"),
+    (&["milliseconds"], "ms"),
+    (&["kilobytes"], "KB"),
+    (&["megabytes"], "MB"),
+    (&["gigabytes"], "GB"),
+    (&["terabytes"], "TB"),
+];
+
+/// A whitespace-separated word with the punctuation around it split off.
+struct Token<'a> {
+    raw: &'a str,
+    lead: &'a str,
+    /// Lowercased core, e.g. "thirty" for "Thirty,".
+    word: String,
+    trail: &'a str,
+    /// Whitespace between this token and the previous one.
+    space_before: &'a str,
+    /// Split from the next token at a hyphen, as in "twenty-three".
+    hyphen_after: bool,
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut previous_end = 0;
+    text.split_whitespace()
+        .flat_map(|raw| {
+            let start = raw.as_ptr() as usize - text.as_ptr() as usize;
+            let space_before = &text[previous_end..start];
+            previous_end = start + raw.len();
+            let core_start = raw.find(|c: char| c.is_alphanumeric()).unwrap_or(raw.len());
+            let core_end = raw.rfind(|c: char| c.is_alphanumeric()).map_or(core_start, |i| i + raw[i..].chars().next().unwrap().len_utf8());
+            let (lead, core, trail) = (&raw[..core_start], &raw[core_start..core_end], &raw[core_end..]);
+            // "twenty-three" reads as two number words
+            let parts: Vec<&str> = core.split('-').collect();
+            let split = parts.len() > 1 && parts.iter().all(|p| classify(&p.to_lowercase()).is_some());
+            if !split {
+                return vec![Token { raw, lead, word: core.to_lowercase(), trail, space_before, hyphen_after: false }];
+            }
+            let last = parts.len() - 1;
+            let mut start = core_start;
+            parts
+                .into_iter()
+                .enumerate()
+                .map(|(i, part)| {
+                    let end = start + part.len();
+                    let token = Token {
+                        raw: &raw[if i == 0 { 0 } else { start }..if i == last { raw.len() } else { end }],
+                        lead: if i == 0 { lead } else { "" },
+                        word: part.to_lowercase(),
+                        trail: if i == last { trail } else { "" },
+                        space_before: if i == 0 { space_before } else { "" },
+                        hyphen_after: i < last,
+                    };
+                    start = end + 1;
+                    token
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Tokens `a` and `a + 1` belong to one phrase: no punctuation or line break between them.
+fn joined(tokens: &[Token], a: usize) -> bool {
+    a + 1 < tokens.len() && tokens[a].trail.is_empty() && tokens[a + 1].lead.is_empty() && !tokens[a + 1].space_before.contains('\n')
+}
+
+/// The word following token `a` in the same phrase.
+fn next_word<'t>(tokens: &'t [Token], a: usize) -> Option<&'t str> {
+    joined(tokens, a).then(|| tokens[a + 1].word.as_str())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+/// Number words as (kind, value, is ordinal).
+fn classify(word: &str) -> Option<(Kind, u64, bool)> {
+    const UNITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+    const TEENS: [&str; 10] = ["ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen"];
+    const TENS: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+    const UNIT_ORDINALS: [&str; 10] = ["zeroth", "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth"];
+    const TEEN_ORDINALS: [&str; 10] = [
+        "tenth", "eleventh", "twelfth", "thirteenth", "fourteenth", "fifteenth", "sixteenth", "seventeenth", "eighteenth", "nineteenth",
+    ];
+    const TENS_ORDINALS: [&str; 8] = ["twentieth", "thirtieth", "fortieth", "fiftieth", "sixtieth", "seventieth", "eightieth", "ninetieth"];
+
+    let position = |list: &[&str]| list.iter().position(|w| *w == word).map(|i| i as u64);
+    if let Some(i) = position(&UNITS) {
+        return Some((Kind::Unit, i, false));
+    }
+    if let Some(i) = position(&TEENS) {
+        return Some((Kind::Teen, 10 + i, false));
+    }
+    if let Some(i) = position(&TENS) {
+        return Some((Kind::Tens, 20 + 10 * i, false));
+    }
+    if let Some(i) = position(&UNIT_ORDINALS) {
+        return Some((Kind::Unit, i, true));
+    }
+    if let Some(i) = position(&TEEN_ORDINALS) {
+        return Some((Kind::Teen, 10 + i, true));
+    }
+    if let Some(i) = position(&TENS_ORDINALS) {
+        return Some((Kind::Tens, 20 + 10 * i, true));
+    }
+    match word {
+        "hundred" => Some((Kind::Hundred, 100, false)),
+        "hundredth" => Some((Kind::Hundred, 100, true)),
+        "thousand" => Some((Kind::Scale, 1_000, false)),
+        "thousandth" => Some((Kind::Scale, 1_000, true)),
+        "million" => Some((Kind::Scale, 1_000_000, false)),
+        "millionth" => Some((Kind::Scale, 1_000_000, true)),
+        "billion" => Some((Kind::Scale, 1_000_000_000, false)),
+        "billionth" => Some((Kind::Scale, 1_000_000_000, true)),
+        _ => None,
+    }
+}
+
+fn is_number_word(token: &Token) -> bool {
+    classify(&token.word).is_some() || token.word.chars().all(|c| c.is_ascii_digit())
+}
+
+fn digit_word(word: &str) -> Option<u64> {
+    match word {
+        "oh" | "o" => Some(0),
+        _ => classify(word).filter(|(kind, _, ordinal)| *kind == Kind::Unit && !ordinal).map(|(_, value, _)| value),
+    }
+}
+
+/// A number read from one or more tokens.
+#[derive(Debug, Default)]
+struct Number {
+    /// Tokens consumed.
+    len: usize,
+    value: u64,
+    /// Digits after "point".
+    decimals: String,
+    ordinal: bool,
+    /// Already written with digits.
+    digits: bool,
+}
+
+impl Number {
+    fn is_whole(&self, range: std::ops::RangeInclusive<u64>) -> bool {
+        self.decimals.is_empty() && !self.ordinal && range.contains(&self.value)
+    }
+}
+
+/// Reads a number starting at token `i`: spoken words ("two hundred and five",
+/// "three point one four", "twenty first") or digits ("1,500", "2.5", "5th").
+fn parse_number(tokens: &[Token], i: usize) -> Option<Number> {
+    let first = tokens.get(i)?;
+    if let Some(number) = parse_digits(&first.word) {
+        return Some(number);
+    }
+
+    let (mut total, mut current) = (0u64, 0u64);
+    let mut last: Option<Kind> = None;
+    let mut ordinal = false;
+    let mut j = i;
+    while j < tokens.len() {
+        if j > i && !joined(tokens, j - 1) {
+            break;
+        }
+        let word = tokens[j].word.as_str();
+        // "a hundred", "a thousand"
+        if j == i && word == "a" && matches!(next_word(tokens, j).and_then(classify), Some((Kind::Hundred | Kind::Scale, _, _))) {
+            current = 1;
+            last = Some(Kind::Unit);
+            j += 1;
+            continue;
+        }
+        // "and" only inside a number: "one hundred and five"
+        if word == "and" && matches!(last, Some(Kind::Hundred | Kind::Scale)) {
+            if matches!(next_word(tokens, j).and_then(classify), Some((Kind::Unit | Kind::Teen | Kind::Tens, _, _))) {
+                j += 1;
+                continue;
+            }
+            break;
+        }
+        let Some((kind, value, is_ordinal)) = classify(word) else { break };
+        let valid = match kind {
+            Kind::Unit | Kind::Teen => match last {
+                None | Some(Kind::Hundred | Kind::Scale) => true,
+                Some(Kind::Tens) => kind == Kind::Unit && value > 0,
+                _ => false,
+            },
+            Kind::Tens => matches!(last, None | Some(Kind::Hundred | Kind::Scale)),
+            Kind::Hundred => matches!(last, Some(Kind::Unit | Kind::Teen | Kind::Tens)) && (1..100).contains(&current),
+            Kind::Scale => current > 0,
+        };
+        if !valid {
+            break;
+        }
+        match kind {
+            Kind::Hundred => current *= 100,
+            Kind::Scale => {
+                total += current * value;
+                current = 0;
+            }
+            _ => current += value,
+        }
+        last = Some(kind);
+        j += 1;
+        if is_ordinal {
+            ordinal = true;
+            break;
+        }
+    }
+    last?;
+
+    let mut number = Number { len: j - i, value: total + current, ordinal, ..Default::default() };
+    // "three point one four"
+    if !ordinal && next_word(tokens, j - 1) == Some("point") {
+        let mut k = j;
+        while let Some(digit) = next_word(tokens, k).and_then(digit_word) {
+            number.decimals.push_str(&digit.to_string());
+            k += 1;
+        }
+        if !number.decimals.is_empty() {
+            number.len = k + 1 - i;
+        }
+    }
+    Some(number)
+}
+
+/// "1500", "1,500", "2.5" or "5th".
+fn parse_digits(word: &str) -> Option<Number> {
+    let (body, ordinal) = match word.strip_suffix("st").or(word.strip_suffix("nd")).or(word.strip_suffix("rd")).or(word.strip_suffix("th")) {
+        Some(body) => (body, true),
+        None => (word, false),
+    };
+    let (int, decimals) = body.split_once('.').unwrap_or((body, ""));
+    let groups: Vec<&str> = int.split(',').collect();
+    let well_formed = groups.iter().all(|g| !g.is_empty() && g.chars().all(|c| c.is_ascii_digit()))
+        && groups[1..].iter().all(|g| g.len() == 3)
+        && decimals.chars().all(|c| c.is_ascii_digit())
+        && (!ordinal || decimals.is_empty());
+    if !well_formed {
+        return None;
+    }
+    let value = groups.concat().parse().ok()?;
+    Some(Number { len: 1, value, decimals: decimals.to_string(), ordinal, digits: true })
+}
+
+/// Thousands are grouped from 10,000 up; smaller numbers read like years.
+fn format_number(number: &Number) -> String {
+    let digits = number.value.to_string();
+    let int = if number.value >= 10_000 {
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        grouped
+    } else {
+        digits
+    };
+    if number.decimals.is_empty() {
+        int
+    } else {
+        format!("{}.{}", int, number.decimals)
+    }
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// A rewritten span of tokens.
+struct Match {
+    len: usize,
+    text: String,
+    /// Replaces the last token's trailing punctuation.
+    trail: Option<String>,
+}
+
+impl Match {
+    fn new(len: usize, text: String) -> Option<Self> {
+        Some(Self { len, text, trail: None })
+    }
+}
+
+/// "nineteen ninety nine", "twenty oh five", "twenty twenty four"
+fn parse_year(tokens: &[Token], i: usize) -> Option<(usize, u64)> {
+    let century = match tokens.get(i)?.word.as_str() {
+        "nineteen" => 19,
+        "twenty" => 20,
+        _ => return None,
+    };
+    let second = next_word(tokens, i)?;
+    if let Some(0) = digit_word(second) {
+        let unit = next_word(tokens, i + 1).and_then(digit_word).filter(|u| *u > 0)?;
+        return Some((3, century * 100 + unit));
+    }
+    match classify(second)? {
+        (Kind::Teen, value, false) => Some((2, century * 100 + value)),
+        (Kind::Tens, value, false) => match next_word(tokens, i + 1).and_then(classify) {
+            Some((Kind::Unit, unit, false)) if unit > 0 => Some((3, century * 100 + value + unit)),
+            _ => Some((2, century * 100 + value)),
+        },
+        _ => None,
+    }
+}
+
+/// A year after a month or day: spoken pairs, "two thousand five" or "2024".
+fn parse_date_year(tokens: &[Token], i: usize) -> Option<(usize, u64)> {
+    parse_year(tokens, i).or_else(|| {
+        parse_number(tokens, i).filter(|n| n.is_whole(1000..=2999)).map(|n| (n.len, n.value))
+    })
+}
+
+fn month(word: &str) -> Option<&'static str> {
+    MONTHS.iter().find(|m| m.eq_ignore_ascii_case(word)).copied()
+}
+
+fn match_date(tokens: &[Token], i: usize) -> Option<Match> {
+    // "the fifth of march" -> "the 5th of March"
+    if let Some(day) = parse_number(tokens, i).filter(|n| n.ordinal && (1..=31).contains(&n.value)) {
+        let of = i + day.len;
+        if next_word(tokens, of - 1) == Some("of") {
+            if let Some(month) = next_word(tokens, of).and_then(month) {
+                return Match::new(day.len + 2, format!("{}{} of {}", day.value, ordinal_suffix(day.value), month));
+            }
+        }
+    }
+
+    let month = month(&tokens[i].word)?;
+    if !joined(tokens, i) {
+        return None;
+    }
+    // Some months are also ordinary words ("you may one day", "we march twenty miles").
+    // Those only take a day that is clearly one, "march fifth" or "august 5", unless
+    // capitalized. "May" even then, as it starts questions ("May I").
+    let capitalized = tokens[i].raw[tokens[i].lead.len()..].starts_with(char::is_uppercase);
+    let verb = month == "May" || (matches!(month, "March" | "August") && !capitalized);
+    // "March twenty twenty four" -> "March 2024"
+    if !verb {
+        if let Some((len, year)) = parse_year(tokens, i + 1) {
+            return Match::new(1 + len, format!("{} {}", month, year));
+        }
+    }
+    let day = parse_number(tokens, i + 1)
+        .filter(|n| n.decimals.is_empty() && (1..=31).contains(&n.value) && (!verb || n.ordinal || n.digits))?;
+    let end = i + day.len;
+    match joined(tokens, end).then(|| parse_date_year(tokens, end + 1)).flatten() {
+        Some((len, year)) => Match::new(1 + day.len + len, format!("{} {}, {}", month, day.value, year)),
+        None => Match::new(1 + day.len, format!("{} {}", month, day.value)),
+    }
+}
+
+fn match_time(tokens: &[Token], i: usize) -> Option<Match> {
+    // "5:30 pm"
+    let (hour, mut minutes, mut len) = match tokens[i].word.split_once(':') {
+        Some((h, m)) if m.len() == 2 && m.chars().all(|c| c.is_ascii_digit()) => (h.parse::<u64>().ok()?, Some(m.to_string()), 1),
+        Some(_) => return None,
+        None => {
+            let hour = parse_number(tokens, i).filter(|n| n.len == 1 && n.is_whole(1..=12))?;
+            (hour.value, None, 1)
+        }
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    if minutes.is_none() {
+        let k = i + len;
+        if joined(tokens, k - 1) {
+            if digit_word(&tokens[k].word) == Some(0) && tokens[k].word != "zero" {
+                // "five oh five"
+                if let Some(unit) = next_word(tokens, k).and_then(digit_word).filter(|u| *u > 0) {
+                    minutes = Some(format!("0{}", unit));
+                    len += 2;
+                }
+            } else if let Some(m) = parse_number(tokens, k).filter(|n| !n.digits && n.is_whole(10..=59)) {
+                minutes = Some(m.value.to_string());
+                len += m.len;
+            }
+        }
+    }
+
+    let suffix = next_word(tokens, i + len - 1)?;
+    // With punctuation stripped, "p.m." and "o'clock" arrive as "p m" and "o clock"
+    let second = next_word(tokens, i + len);
+    let (meridiem, suffix_len) = match (suffix.replace('.', "").as_str(), second) {
+        ("am", _) => ("AM", 1),
+        ("pm", _) => ("PM", 1),
+        ("a", Some("m")) => ("AM", 2),
+        ("p", Some("m")) => ("PM", 2),
+        ("o'clock" | "o’clock", _) if minutes.is_none() => return Match::new(len + 1, format!("{} o'clock", hour)),
+        ("o", Some("clock")) if minutes.is_none() => return Match::new(len + 2, format!("{} o'clock", hour)),
+        _ => return None,
+    };
+    let time = match minutes {
+        Some(minutes) => format!("{}:{} {}", hour, minutes, meridiem),
+        None => format!("{} {}", hour, meridiem),
+    };
+    // The period of "p.m." ends the abbreviation, and the sentence only if nothing follows
+    let last = i + len + suffix_len - 1;
+    let mut trail = tokens[last].trail.to_string();
+    if suffix.contains('.') && trail.starts_with('.') && last + 1 < tokens.len() {
+        trail.remove(0);
+    }
+    Some(Match { len: len + suffix_len, text: time, trail: Some(trail) })
+}
+
+fn currency_symbol(word: &str) -> Option<&'static str> {
+    match word {
+        "dollar" | "dollars" => Some("$"),
+        "euro" | "euros" => Some("€"),
+        _ => None,
+    }
+}
+
+fn match_currency(tokens: &[Token], i: usize) -> Option<Match> {
+    let amount = parse_number(tokens, i).filter(|n| !n.ordinal)?;
+    let end = i + amount.len - 1;
+    // "nineteen ninety nine dollars" is a price, not a year: $19.99
+    if !amount.digits && amount.is_whole(1..=99) && joined(tokens, end) {
+        if let Some(cents) = parse_number(tokens, end + 1).filter(|n| !n.digits && n.is_whole(10..=99)) {
+            if let Some(symbol) = next_word(tokens, end + cents.len).and_then(currency_symbol) {
+                return Match::new(amount.len + cents.len + 1, format!("{}{}.{}", symbol, amount.value, cents.value));
+            }
+        }
+    }
+    let symbol = currency_symbol(next_word(tokens, end)?)?;
+    // "ten dollars and fifty cents"
+    if amount.decimals.is_empty() && next_word(tokens, end + 1) == Some("and") {
+        if let Some(cents) = joined(tokens, end + 2).then(|| parse_number(tokens, end + 3)).flatten().filter(|n| !n.digits && n.is_whole(1..=99)) {
+            if matches!(next_word(tokens, end + 2 + cents.len), Some("cent" | "cents")) {
+                let len = amount.len + 3 + cents.len;
+                return Match::new(len, format!("{}{}.{:02}", symbol, format_number(&amount), cents.value));
+            }
+        }
+    }
+    Match::new(amount.len + 1, format!("{}{}", symbol, format_number(&amount)))
+}
+
+fn match_percentage(tokens: &[Token], i: usize) -> Option<Match> {
+    let amount = parse_number(tokens, i).filter(|n| !n.ordinal)?;
+    let end = i + amount.len - 1;
+    let len = match next_word(tokens, end)? {
+        "percent" => 1,
+        "per" if next_word(tokens, end + 1) == Some("cent") => 2,
+        _ => return None,
+    };
+    Match::new(amount.len + len, format!("{}%", format_number(&amount)))
+}
+
+fn match_unit(tokens: &[Token], i: usize) -> Option<Match> {
+    let amount = parse_number(tokens, i).filter(|n| !n.ordinal)?;
+    let end = i + amount.len - 1;
+    let (words, symbol) = UNIT_SYMBOLS
+        .iter()
+        .find(|(words, _)| words.iter().enumerate().all(|(k, word)| next_word(tokens, end + k) == Some(*word)))?;
+    let value = format_number(&amount);
+    let text = if symbol.starts_with('°') { format!("{}{}", value, symbol) } else { format!("{} {}", value, symbol) };
+    Match::new(amount.len + words.len(), text)
+}
+
+/// Numbers and ordinals on their own. Runs of separate numbers ("twelve fifteen")
+/// are ambiguous and stay as spoken.
+fn match_plain_number(tokens: &[Token], i: usize, options: &ItnOptions) -> Option<Match> {
+    let number = parse_number(tokens, i).filter(|n| !n.digits)?;
+    let enabled = if number.ordinal { options.ordinals } else { options.numbers };
+    // One to nine read better as words
+    let single = number.len == 1 && number.value < 10;
+    if !enabled || single {
+        return None;
+    }
+    let end = i + number.len - 1;
+    let after_number = i > 0 && joined(tokens, i - 1) && is_number_word(&tokens[i - 1]);
+    let before_number = joined(tokens, end) && is_number_word(&tokens[end + 1]);
+    if after_number || before_number {
+        return None;
+    }
+    let text = if number.ordinal {
+        format!("{}{}", format_number(&number), ordinal_suffix(number.value))
+    } else {
+        format_number(&number)
+    };
+    Match::new(number.len, text)
+}
+
+fn match_at(tokens: &[Token], i: usize, options: &ItnOptions) -> Option<Match> {
+    options.dates.then(|| match_date(tokens, i)).flatten()
+        .or_else(|| options.times.then(|| match_time(tokens, i)).flatten())
+        .or_else(|| options.currency.then(|| match_currency(tokens, i)).flatten())
+        .or_else(|| options.percentages.then(|| match_percentage(tokens, i)).flatten())
+        .or_else(|| options.units.then(|| match_unit(tokens, i)).flatten())
+        .or_else(|| options.dates.then(|| parse_year(tokens, i)).flatten().and_then(|(len, year)| Match::new(len, year.to_string())))
+        .or_else(|| match_plain_number(tokens, i, options))
+}
+
+/// Rewrites spoken forms in `text`. Text without any is returned unchanged.
+pub fn normalize(text: &str, options: &ItnOptions) -> String {
+    let tokens = tokenize(text);
+    let mut output = String::with_capacity(text.len());
+    let mut changed = false;
+    // Whether the previous token was copied as is
+    let mut copied = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        // Keep the hyphen of a split word that stays as spoken, and line breaks
+        if i > 0 && tokens[i - 1].hyphen_after {
+            output.push(if copied { '-' } else { ' ' });
+        }
+        output.push_str(tokens[i].space_before);
+        match match_at(&tokens, i, options) {
+            Some(m) => {
+                let trail = m.trail.as_deref().unwrap_or(tokens[i + m.len - 1].trail);
+                output.push_str(tokens[i].lead);
+                output.push_str(&m.text);
+                output.push_str(trail);
+                changed = true;
+                copied = false;
+                i += m.len;
+            }
+            None => {
+                output.push_str(tokens[i].raw);
+                copied = true;
+                i += 1;
+            }
+        }
+    }
+    if changed {
+        let end = tokens.last().map_or(0, |t| t.raw.as_ptr() as usize - text.as_ptr() as usize + t.raw.len());
+        output.push_str(&text[end..]);
+        output
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(cases: &[(&str, &str)], options: &ItnOptions) {
+        for (input, expected) in cases {
+            assert_eq!(normalize(input, options), *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_numbers() {
+        check(
+            &[
+                ("twenty three", "23"),
+                ("Twenty-three people came.", "23 people came."),
+                ("one hundred and five", "105"),
+                ("two thousand five hundred", "2500"),
+                ("a hundred times", "100 times"),
+                ("three point one four", "3.14"),
+                ("version two point oh", "version 2.0"),
+                ("forty two million", "42,000,000"),
+                ("twelve", "12"),
+                // One to nine stay words
+                ("one of the two options", "one of the two options"),
+                ("I have three cats", "I have three cats"),
+                // Runs of separate numbers are ambiguous
+                ("twelve fifteen", "twelve fifteen"),
+                ("one two three", "one two three"),
+                ("twenty, thirty", "20, 30"),
+                // Whisper's own digits pass through
+                ("I have 23 cats", "I have 23 cats"),
+                ("pick one and two", "pick one and two"),
+            ],
+            &ItnOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_ordinals() {
+        check(
+            &[
+                ("the twenty first century", "the 21st century"),
+                ("my thirty-second birthday", "my 32nd birthday"),
+                ("the eleventh hour", "the 11th hour"),
+                ("one hundredth", "100th"),
+                ("first of all", "first of all"),
+                ("in second place", "in second place"),
+            ],
+            &ItnOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_percentages() {
+        check(
+            &[
+                ("five percent", "5%"),
+                ("twenty five per cent.", "25%."),
+                ("one hundred percent sure", "100% sure"),
+                ("it rose 23 percent", "it rose 23%"),
+                ("zero point five percent", "0.5%"),
+            ],
+            &ItnOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_times() {
+        check(
+            &[
+                ("five thirty pm", "5:30 PM"),
+                ("at five p.m. tomorrow", "at 5 PM tomorrow"),
+                ("see you at five p.m.", "see you at 5 PM."),
+                ("ten fifteen am", "10:15 AM"),
+                ("six oh five am", "6:05 AM"),
+                ("eleven forty five pm", "11:45 PM"),
+                ("five o'clock", "5 o'clock"),
+                // As left by punctuation stripping
+                ("five thirty p m", "5:30 PM"),
+                ("ten o clock", "10 o'clock"),
+                ("5:30 pm", "5:30 PM"),
+                ("twenty pm", "20 pm"),
+                ("I am here", "I am here"),
+            ],
+            &ItnOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_dates() {
+        check(
+            &[
+                ("March fifth", "March 5"),
+                ("march twenty third", "March 23"),
+                ("on March fifth twenty twenty four", "on March 5, 2024"),
+                ("June 3 2021", "June 3, 2021"),
+                ("December first, two thousand five", "December 1, 2005"),
+                ("December first two thousand five", "December 1, 2005"),
+                ("October twenty twenty", "October 2020"),
+                ("the fifth of November", "the 5th of November"),
+                ("in nineteen ninety nine", "in 1999"),
+                ("twenty oh five", "2005"),
+                ("May fifth", "May 5"),
+                ("you may one day", "you may one day"),
+                ("May I", "May I"),
+                ("August twenty", "August 20"),
+                ("august fifth", "August 5"),
+                ("we march twenty miles", "we march 20 miles"),
+                ("an august twenty member panel", "an august 20 member panel"),
+            ],
+            &ItnOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_currency() {
+        check(
+            &[
+                ("ten dollars", "$10"),
+                ("ten dollars and fifty cents", "$10.50"),
+                ("one dollar and five cents", "$1.05"),
+                ("twenty euros", "€20"),
+                ("a thousand dollars", "$1000"),
+                ("fifty thousand dollars", "$50,000"),
+                ("it costs 15 dollars.", "it costs $15."),
+                ("ten dollars and change", "$10 and change"),
+                ("nineteen ninety nine dollars", "$19.99"),
+                ("it was twelve fifty euros", "it was €12.50"),
+                ("twenty five dollars", "$25"),
+            ],
+            &ItnOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_units() {
+        check(
+            &[
+                ("five kilometers", "5 km"),
+                ("sixty miles per hour", "60 mph"),
+                ("twenty degrees celsius", "20°C"),
+                ("ninety degrees", "90°"),
+                ("two hundred milliseconds", "200 ms"),
+                ("sixteen gigabytes of RAM", "16 GB of RAM"),
+                ("one point five liters", "1.5 L"),
+                ("five pounds", "five pounds"),
+            ],
+            &ItnOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_categories_toggle() {
+        let none = ItnOptions { numbers: false, ordinals: false, percentages: false, times: false, dates: false, currency: false, units: false };
+        assert!(!none.any());
+        let text = "twenty three people paid ten dollars at five pm";
+        assert_eq!(normalize(text, &none), text);
+
+        let currency_only = ItnOptions { currency: true, ..none };
+        assert_eq!(normalize(text, &currency_only), "twenty three people paid $10 at five pm");
+        let numbers_only = ItnOptions { numbers: true, ..none };
+        assert_eq!(normalize(text, &numbers_only), "23 people paid 10 dollars at five pm");
+        assert_eq!(normalize("the twenty first", &numbers_only), "the twenty first");
+        // Without dates, year-like pairs are just separate numbers
+        assert_eq!(normalize("nineteen ninety", &numbers_only), "nineteen ninety");
+
+        // Missing categories keep their defaults
+        let partial: ItnOptions = serde_json::from_str(r#"{ "dates": false }"#).unwrap();
+        assert_eq!(partial, ItnOptions { dates: false, ..Default::default() });
+    }
+
+    #[test]
+    fn test_whitespace_is_kept() {
+        let text = "  Hello,   world!  ";
+        assert_eq!(InverseNormalizer::new(ItnOptions::default()).process(text), text);
+        // Line breaks also end a phrase
+        assert_eq!(normalize(" twenty three\nfive\n\nthirty pm ", &ItnOptions::default()), " 23\nfive\n\n30 pm ");
+    }
+}
@@ -0,0 +1,9 @@
+pub mod continuity;
+pub mod disfluency;
+pub mod itn;
+pub mod pipeline;
+pub mod punctuation;
+pub mod spoken_punctuation;
+
+pub use continuity::Continuity;
+pub use pipeline::{PipelineConfig, TextPipeline, TextProcessor};
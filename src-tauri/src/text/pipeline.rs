@@ -0,0 +1,96 @@
+use log::debug;
+
+use super::disfluency::{DisfluencyCleaner, DisfluencyConfig, DisfluencyMode};
+use super::itn::{InverseNormalizer, ItnOptions};
+use super::punctuation::StripPunctuation;
+use super::spoken_punctuation::SpokenPunctuation;
+
+/// One step of post-processing between the transcription engine and the injector.
+pub trait TextProcessor: Send {
+    /// Short name for logs.
+    fn name(&self) -> &'static str;
+
+    fn process(&mut self, text: &str) -> String;
+}
+
+/// Which stages run. Compared on every transcript, so settings changes rebuild the pipeline.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipelineConfig {
+    pub disfluency: DisfluencyConfig,
+    pub strip_punctuation: bool,
+    /// Punctuation dictated as words: "comma", "new line", ...
+    pub spoken_punctuation: bool,
+    /// Inverse text normalization, `None` when the transcript isn't English.
+    pub itn: Option<ItnOptions>,
+}
+
+/// Ordered post-processing stages for a single utterance. Joining it to the text typed
+/// before is left to `Continuity`.
+pub struct TextPipeline {
+    config: PipelineConfig,
+    stages: Vec<Box<dyn TextProcessor>>,
+}
+
+impl TextPipeline {
+    pub fn new(config: PipelineConfig) -> Self {
+        let mut stages: Vec<Box<dyn TextProcessor>> = Vec::new();
+        // First, while Whisper's commas still mark fillers and corrections
+        if config.disfluency.mode != DisfluencyMode::Off {
+            stages.push(Box::new(DisfluencyCleaner::new(&config.disfluency)));
+        }
+        if config.strip_punctuation {
+            stages.push(Box::new(StripPunctuation));
+        }
+        // After stripping, so dictated marks and "$10" or "5:30 PM" keep their symbols
+        if config.spoken_punctuation {
+            stages.push(Box::new(SpokenPunctuation));
+        }
+        if let Some(itn) = config.itn.filter(ItnOptions::any) {
+            stages.push(Box::new(InverseNormalizer::new(itn)));
+        }
+        Self { config, stages }
+    }
+
+    pub fn config(&self) -> &PipelineConfig {
+        &self.config
+    }
+
+    pub fn process(&mut self, text: &str) -> String {
+        let mut text = text.to_string();
+        for stage in &mut self.stages {
+            let next = stage.process(&text);
+            if next != text {
+                debug!("✏️ {}: '{}' -> '{}'", stage.name(), text, next);
+            }
+            text = next;
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages_run_in_order() {
+        let stripped = PipelineConfig { strip_punctuation: true, ..Default::default() };
+        let mut pipeline = TextPipeline::new(stripped.clone());
+        assert_eq!(pipeline.process("Hello, world."), "Hello world");
+
+        let mut pipeline = TextPipeline::new(PipelineConfig { itn: Some(ItnOptions::default()), ..stripped.clone() });
+        assert_eq!(pipeline.process("Meet at five p.m., bring ten dollars."), "Meet at 5 PM bring $10");
+
+        // Dictated marks survive stripping, then numbers are written out around them
+        let mut pipeline = TextPipeline::new(PipelineConfig { spoken_punctuation: true, itn: Some(ItnOptions::default()), ..stripped.clone() });
+        assert_eq!(pipeline.process("Pay ten dollars, comma, today. Period."), "Pay $10, today.");
+
+        // Fillers go before the commas around them are stripped
+        let disfluency = DisfluencyConfig { mode: DisfluencyMode::Aggressive, ..Default::default() };
+        let mut pipeline = TextPipeline::new(PipelineConfig { disfluency, itn: Some(ItnOptions::default()), ..stripped });
+        assert_eq!(pipeline.process("Um, meet at five, no, six p.m."), "Meet at 6 PM");
+
+        let mut passthrough = TextPipeline::new(PipelineConfig::default());
+        assert_eq!(passthrough.process("Hello, world."), "Hello, world.");
+    }
+}
@@ -0,0 +1,32 @@
+use super::pipeline::TextProcessor;
+
+/// Replaces ASCII punctuation with spaces, keeping words apart, and collapses the
+/// runs of spaces that leaves.
+pub struct StripPunctuation;
+
+impl TextProcessor for StripPunctuation {
+    fn name(&self) -> &'static str {
+        "strip punctuation"
+    }
+
+    fn process(&mut self, text: &str) -> String {
+        text.split(|c: char| c.is_ascii_punctuation() || c == ' ')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_punctuation_becomes_word_breaks() {
+        assert_eq!(StripPunctuation.process(" Hello, world. How are you? "), "Hello world How are you");
+        assert_eq!(StripPunctuation.process("one,two"), "one two");
+        // Non-ASCII text is left alone
+        assert_eq!(StripPunctuation.process("Grüße, Zoë!"), "Grüße Zoë");
+        assert_eq!(StripPunctuation.process("..."), "");
+    }
+}
@@ -0,0 +1,196 @@
+use super::pipeline::TextProcessor;
+
+/// What a spoken command types and how it sits between words.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mark {
+    /// Attached to the previous word: "hello," or "done."
+    Attached(&'static str),
+    /// Attached to the next word, as an opening quote.
+    Opening(&'static str),
+    /// Line breaks, with no spaces around them.
+    Break(&'static str),
+}
+
+/// Two-word commands first, so "question mark" isn't read as a lone word.
+const COMMANDS: &[(&[&str], Mark)] = &[
+    (&["question", "mark"], Mark::Attached("?")),
+    (&["exclamation", "mark"], Mark::Attached("!")),
+    (&["exclamation", "point"], Mark::Attached("!")),
+    (&["full", "stop"], Mark::Attached(".")),
+    (&["open", "quote"], Mark::Opening("\"")),
+    (&["close", "quote"], Mark::Attached("\"")),
+    (&["end", "quote"], Mark::Attached("\"")),
+    (&["new", "paragraph"], Mark::Break("\n\n")),
+    (&["new", "line"], Mark::Break("\n")),
+    (&["comma"], Mark::Attached(",")),
+    (&["period"], Mark::Attached(".")),
+    (&["colon"], Mark::Attached(":")),
+];
+
+/// Punctuation Whisper guesses around spoken commands ("Hello, comma, how...").
+/// It gives way to the spoken mark.
+const GUESSED: &[char] = &[',', '.', ';', ':', '!', '?'];
+
+/// Words after which "comma", "period" or "colon" is a noun: "add a comma", "my colon".
+const DETERMINERS: &[&str] = &[
+    "a", "an", "the", "this", "that", "these", "those", "my", "your", "his", "her", "its",
+    "our", "their", "each", "every", "another", "any", "some", "no", "per",
+];
+
+/// Types the command after it as words: "literal comma" types "comma".
+const LITERAL: &str = "literal";
+
+/// Turns spoken punctuation anywhere in an utterance into the marks themselves:
+/// "hello comma how are you question mark" becomes "hello, how are you?".
+pub struct SpokenPunctuation;
+
+impl TextProcessor for SpokenPunctuation {
+    fn name(&self) -> &'static str {
+        "spoken punctuation"
+    }
+
+    fn process(&mut self, text: &str) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut output = String::with_capacity(text.len());
+        let mut changed = false;
+        // No space before the next word: start of text, after an opening quote or a line break
+        let mut attach_next = true;
+        let mut capitalize_next = false;
+        // Words up to here are typed as is, after "literal"
+        let mut literal_until = 0;
+        let mut i = 0;
+        while i < words.len() {
+            if words[i].eq_ignore_ascii_case(LITERAL) {
+                if let Some((len, _)) = command_at(&words, i + 1) {
+                    literal_until = i + 1 + len;
+                    changed = true;
+                    i += 1;
+                    continue;
+                }
+            }
+            let command = command_at(&words, i).filter(|&(len, _)| i >= literal_until && (len > 1 || !reads_as_noun(&words, i)));
+            let Some((len, mark)) = command else {
+                if !attach_next {
+                    output.push(' ');
+                }
+                if capitalize_next {
+                    output.push_str(&capitalize(words[i]));
+                } else {
+                    output.push_str(words[i]);
+                }
+                attach_next = false;
+                capitalize_next = false;
+                i += 1;
+                continue;
+            };
+            match mark {
+                Mark::Attached(symbol) => {
+                    output.truncate(output.trim_end_matches(GUESSED).len());
+                    output.push_str(symbol);
+                    attach_next = false;
+                    capitalize_next = matches!(symbol, "." | "?" | "!");
+                }
+                Mark::Opening(symbol) => {
+                    if !attach_next {
+                        output.push(' ');
+                    }
+                    output.push_str(symbol);
+                    attach_next = true;
+                }
+                Mark::Break(symbol) => {
+                    output.truncate(output.trim_end_matches(' ').len());
+                    output.push_str(symbol);
+                    attach_next = true;
+                    capitalize_next = true;
+                }
+            }
+            changed = true;
+            i += len;
+        }
+        if changed {
+            output
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// The command starting at word `i`, with the number of words it spans.
+fn command_at(words: &[&str], i: usize) -> Option<(usize, Mark)> {
+    COMMANDS.iter().find_map(|(phrase, mark)| {
+        let matches = phrase.len() <= words.len() - i
+            && phrase.iter().zip(&words[i..]).all(|(expected, word)| {
+                word.trim_matches(|c: char| !c.is_alphanumeric()).eq_ignore_ascii_case(expected)
+            });
+        matches.then_some((phrase.len(), *mark))
+    })
+}
+
+/// Whether a one-word command is meant as the noun: right after a determiner ("add a
+/// comma here"), or inside a noun phrase that goes on ("the trial period ended"). Words
+/// before punctuation belong to an earlier clause and don't count.
+fn reads_as_noun(words: &[&str], i: usize) -> bool {
+    let clause: Vec<&str> = words[..i].iter().rev().take(2).take_while(|w| w.ends_with(char::is_alphanumeric)).copied().collect();
+    let determiner = |word: &str| DETERMINERS.iter().any(|d| word.eq_ignore_ascii_case(d));
+    let goes_on = i + 1 < words.len() && words[i].ends_with(char::is_alphanumeric);
+    match clause.as_slice() {
+        [previous, ..] if determiner(previous) => true,
+        [_, before] => determiner(before) && goes_on,
+        _ => false,
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_attach_with_spacing() {
+        let cases = [
+            ("hello comma how are you question mark", "hello, how are you?"),
+            ("dear team colon the build is green period ship it", "dear team: the build is green. Ship it"),
+            ("he said open quote hello close quote and left", "he said \"hello\" and left"),
+            ("first line new line second line", "first line\nSecond line"),
+            ("end of section new paragraph next section", "end of section\n\nNext section"),
+            ("wow exclamation point", "wow!"),
+            ("comma", ","),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(SpokenPunctuation.process(input), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_whisper_punctuation_gives_way() {
+        assert_eq!(SpokenPunctuation.process("Hello, comma, how are you? Question mark."), "Hello, how are you?");
+        assert_eq!(SpokenPunctuation.process("Done. New line. Thanks."), "Done.\nThanks.");
+        // Text without commands is left exactly as it was
+        assert_eq!(SpokenPunctuation.process(" Hello,  world. "), " Hello,  world. ");
+    }
+
+    #[test]
+    fn test_words_meant_as_nouns_stay() {
+        let cases = [
+            ("the trial period ended", "the trial period ended"),
+            ("add a comma here", "add a comma here"),
+            ("my colon hurts", "my colon hurts"),
+            ("The trial period ended.", "The trial period ended."),
+            // At the end of a clause it's still a command
+            ("thanks to the team period", "thanks to the team."),
+            ("the build is green period ship it", "the build is green. Ship it"),
+            ("type literal comma here", "type comma here"),
+            ("say literal question mark please", "say question mark please"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(SpokenPunctuation.process(input), expected, "input: {:?}", input);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+
+use super::confidence::Confidence;
+use super::engine::TranscriptionOptions;
+
+/// Output of one transcription pass.
+#[derive(Debug)]
+pub struct Decoded {
+    pub text: String,
+    /// Language code, set when the language was auto-detected.
+    pub language: Option<String>,
+    pub confidence: Confidence,
+}
+
+/// Something that turns 16kHz mono audio into text: the in-process Whisper model or a
+/// transcription server.
+pub trait TranscriptionBackend: Send {
+    /// Short name for logs.
+    fn name(&self) -> &'static str;
+
+    fn transcribe(&mut self, audio: &[f32], options: &TranscriptionOptions, initial_prompt: Option<&str>) -> Result<Decoded>;
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+/// Probabilities a backend reported for one segment.
+#[derive(Debug, Clone, Default)]
+pub struct SegmentScore {
+    /// Probability that the segment's audio holds no speech at all, if reported.
+    pub no_speech_probability: Option<f32>,
+    /// Probability of each text token, special tokens excluded. Empty if not reported.
+    pub token_probabilities: Vec<f32>,
+}
+
+/// How sure the backend was about one segment. `None` where it reported no score.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SegmentConfidence {
+    /// Mean token probability, 0..1.
+    pub probability: Option<f32>,
+    pub no_speech_probability: Option<f32>,
+}
+
+/// How sure the backend was about a whole utterance. Scores are `None` when it reported
+/// none (some transcription servers), so the filter can't judge them either way.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Confidence {
+    /// Mean token probability across all segments, 0..1.
+    pub probability: Option<f32>,
+    /// Highest no-speech probability of any segment, 0..1.
+    pub no_speech_probability: Option<f32>,
+    pub segments: Vec<SegmentConfidence>,
+}
+
+impl Confidence {
+    pub fn from_segments(segments: &[SegmentScore]) -> Self {
+        let tokens: Vec<f32> = segments.iter().flat_map(|s| s.token_probabilities.iter().copied()).collect();
+        let no_speech_probability = segments.iter().filter_map(|s| s.no_speech_probability).reduce(f32::max);
+        let segments = segments
+            .iter()
+            .map(|s| SegmentConfidence { probability: mean(&s.token_probabilities), no_speech_probability: s.no_speech_probability })
+            .collect();
+        Self { probability: mean(&tokens), no_speech_probability, segments }
+    }
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.probability {
+            Some(probability) => write!(f, "p={:.2}", probability),
+            None => write!(f, "unscored"),
+        }
+    }
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// Rejects utterances Whisper itself wasn't sure about. Hallucinations such as
+/// "Thank you for watching." after a cough typically score low on both counts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfidenceFilter {
+    pub enabled: bool,
+    /// Utterances whose mean token probability is below this are dropped.
+    pub min_probability: f32,
+    /// Utterances whose no-speech probability is above this are dropped.
+    pub max_no_speech_probability: f32,
+}
+
+impl Default for ConfidenceFilter {
+    fn default() -> Self {
+        Self { enabled: true, min_probability: 0.4, max_no_speech_probability: 0.6 }
+    }
+}
+
+impl ConfidenceFilter {
+    /// Returns why the utterance should be dropped, if it should. Scores that weren't
+    /// reported aren't checked.
+    pub fn rejection(&self, confidence: &Confidence) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        if let Some(no_speech_probability) = confidence.no_speech_probability.filter(|&p| p > self.max_no_speech_probability) {
+            return Some(format!("likely no speech ({:.2} > {:.2})", no_speech_probability, self.max_no_speech_probability));
+        }
+        if let Some(probability) = confidence.probability.filter(|&p| p < self.min_probability) {
+            return Some(format!("low confidence ({:.2} < {:.2})", probability, self.min_probability));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(no_speech_probability: f32, token_probabilities: &[f32]) -> SegmentScore {
+        SegmentScore { no_speech_probability: Some(no_speech_probability), token_probabilities: token_probabilities.to_vec() }
+    }
+
+    fn scored(probability: f32, no_speech_probability: f32) -> Confidence {
+        Confidence { probability: Some(probability), no_speech_probability: Some(no_speech_probability), segments: Vec::new() }
+    }
+
+    #[test]
+    fn test_confidence_spans_all_segments() {
+        let confidence = Confidence::from_segments(&[segment(0.1, &[0.9, 0.7]), segment(0.3, &[0.8])]);
+        assert!((confidence.probability.unwrap() - 0.8).abs() < 1e-6);
+        assert_eq!(confidence.no_speech_probability, Some(0.3));
+        assert_eq!(confidence.segments.len(), 2);
+        assert!((confidence.segments[0].probability.unwrap() - 0.8).abs() < 1e-6);
+        assert_eq!(confidence.segments[1], SegmentConfidence { probability: Some(0.8), no_speech_probability: Some(0.3) });
+
+        assert_eq!(Confidence::from_segments(&[]).probability, None);
+    }
+
+    #[test]
+    fn test_filter_rejects_with_reason() {
+        let filter = ConfidenceFilter::default();
+
+        let clear = scored(0.85, 0.05);
+        assert!(filter.rejection(&clear).is_none());
+
+        let cough = scored(0.85, 0.9);
+        assert!(filter.rejection(&cough).unwrap().starts_with("likely no speech"));
+
+        let mumble = scored(0.2, 0.1);
+        assert!(filter.rejection(&mumble).unwrap().starts_with("low confidence"));
+
+        let disabled = ConfidenceFilter { enabled: false, ..filter };
+        assert!(disabled.rejection(&mumble).is_none());
+
+        // Unscored results can't be judged, but a reported score still counts
+        let unscored = Confidence::from_segments(&[SegmentScore::default()]);
+        assert!(filter.rejection(&unscored).is_none());
+        assert_eq!(unscored.to_string(), "unscored");
+        let silent = Confidence::from_segments(&[SegmentScore { no_speech_probability: Some(0.9), ..Default::default() }]);
+        assert!(filter.rejection(&silent).is_some());
+    }
+}
@@ -0,0 +1,143 @@
+use std::time::{Duration, Instant};
+
+/// How much of the previous transcript is carried into the next prompt.
+/// Roughly 30-50 tokens, enough for casing, punctuation and names to carry over.
+const MAX_CONTEXT_CHARS: usize = 200;
+
+/// Remembers the tail of recent transcripts so the next utterance can continue them.
+///
+/// The context belongs to one window: text typed elsewhere, or after a long pause,
+/// says nothing about what comes next and is dropped.
+pub struct ContextTracker {
+    text: String,
+    /// Window the text was typed into, when the platform can tell.
+    window: Option<u64>,
+    updated: Option<Instant>,
+    idle_reset: Duration,
+}
+
+impl ContextTracker {
+    pub fn new(idle_reset: Duration) -> Self {
+        Self { text: String::new(), window: None, updated: None, idle_reset }
+    }
+
+    pub fn set_idle_reset(&mut self, idle_reset: Duration) {
+        self.idle_reset = idle_reset;
+    }
+
+    pub fn idle_reset(&self) -> Duration {
+        self.idle_reset
+    }
+
+    pub fn reset(&mut self) {
+        self.text.clear();
+        self.window = None;
+        self.updated = None;
+    }
+
+    /// Adds a finished transcript typed into `window`.
+    pub fn record(&mut self, text: &str, window: Option<u64>, now: Instant) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if self.is_stale(now) || self.switched_from(window) {
+            self.text.clear();
+        }
+        if !self.text.is_empty() {
+            self.text.push(' ');
+        }
+        self.text.push_str(text);
+        self.trim_to_tail();
+        self.window = window;
+        self.updated = Some(now);
+    }
+
+    /// Drops the context when focus has moved to another window since the last transcript.
+    pub fn observe_focus(&mut self, window: Option<u64>) {
+        if self.switched_from(window) {
+            self.reset();
+        }
+    }
+
+    /// The context to prompt with, unless it has gone stale.
+    pub fn tail(&self, now: Instant) -> Option<&str> {
+        (!self.text.is_empty() && !self.is_stale(now)).then_some(self.text.as_str())
+    }
+
+    fn is_stale(&self, now: Instant) -> bool {
+        self.updated.is_some_and(|updated| now.saturating_duration_since(updated) > self.idle_reset)
+    }
+
+    /// Unknown windows never count as a switch.
+    fn switched_from(&self, window: Option<u64>) -> bool {
+        matches!((self.window, window), (Some(previous), Some(current)) if previous != current)
+    }
+
+    /// Keeps the last `MAX_CONTEXT_CHARS`, starting at a word boundary.
+    fn trim_to_tail(&mut self) {
+        let excess = self.text.chars().count().saturating_sub(MAX_CONTEXT_CHARS);
+        if excess == 0 {
+            return;
+        }
+        let cut = self.text.char_indices().nth(excess).map_or(self.text.len(), |(i, _)| i);
+        let start = self.text[cut..].find(' ').map_or(cut, |space| cut + space + 1);
+        self.text.replace_range(..start, "");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_accumulates_and_expires() {
+        let t0 = Instant::now();
+        let mut context = ContextTracker::new(Duration::from_secs(30));
+        assert!(context.tail(t0).is_none());
+
+        context.record("We met Dr. Okafor", Some(1), t0);
+        context.record("and she agreed.", Some(1), t0 + Duration::from_secs(5));
+        assert_eq!(context.tail(t0 + Duration::from_secs(10)), Some("We met Dr. Okafor and she agreed."));
+
+        // Idle too long: nothing is offered, and the next transcript starts fresh
+        let later = t0 + Duration::from_secs(60);
+        assert!(context.tail(later).is_none());
+        context.record("New topic.", Some(1), later);
+        assert_eq!(context.tail(later), Some("New topic."));
+    }
+
+    #[test]
+    fn test_focus_change_resets_context() {
+        let t0 = Instant::now();
+        let mut context = ContextTracker::new(Duration::from_secs(30));
+        context.record("Dear Priya,", Some(1), t0);
+
+        // Unknown focus keeps the context
+        context.observe_focus(None);
+        assert!(context.tail(t0).is_some());
+        context.observe_focus(Some(1));
+        assert!(context.tail(t0).is_some());
+
+        context.observe_focus(Some(2));
+        assert!(context.tail(t0).is_none());
+
+        // Text typed into another window replaces, rather than extends, the context
+        context.record("Dear Priya,", Some(1), t0);
+        context.record("git status", Some(3), t0);
+        assert_eq!(context.tail(t0), Some("git status"));
+    }
+
+    #[test]
+    fn test_context_keeps_word_aligned_tail() {
+        let t0 = Instant::now();
+        let mut context = ContextTracker::new(Duration::from_secs(30));
+        for i in 0..100 {
+            context.record(&format!("word{}", i), None, t0);
+        }
+        let tail = context.tail(t0).unwrap();
+        assert!(tail.chars().count() <= MAX_CONTEXT_CHARS);
+        assert!(tail.starts_with("word"));
+        assert!(tail.ends_with("word99"));
+    }
+}
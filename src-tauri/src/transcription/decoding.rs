@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sampling {
+    /// Takes the most likely token at every step.
+    Greedy,
+    /// Keeps `beam_size` candidate transcripts. More accurate, several times slower.
+    BeamSearch,
+}
+
+/// Whisper decoding knobs. Read for every utterance, so changes need no model reload.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecodingParams {
+    /// CPU threads for decoding. 0 uses whisper's default, min(4, cores).
+    pub threads: u32,
+    pub sampling: Sampling,
+    /// Used by beam search.
+    pub beam_size: u32,
+    pub temperature: f32,
+    /// Added to the temperature to retry a pass that looks like a hallucination or a
+    /// repetition loop. 0 disables the retries.
+    pub temperature_increment: f32,
+    /// Don't start a transcript with a blank.
+    pub suppress_blank: bool,
+    /// Don't condition a long utterance's later windows on its earlier text.
+    pub no_context: bool,
+    /// Produce a single segment. Faster, fine for dictation-length audio.
+    pub single_segment: bool,
+    /// Maximum tokens per segment. 0 = unlimited.
+    pub max_tokens: u32,
+}
+
+impl Default for DecodingParams {
+    fn default() -> Self {
+        DecodingPreset::Balanced.params().unwrap()
+    }
+}
+
+/// Named starting points for the decoding parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodingPreset {
+    Fastest,
+    #[default]
+    Balanced,
+    Accurate,
+    /// Parameters edited by hand.
+    Custom,
+}
+
+impl DecodingPreset {
+    /// The preset's parameters, or `None` for `Custom`.
+    pub fn params(self) -> Option<DecodingParams> {
+        // Whisper's own defaults, with greedy decoding
+        let balanced = DecodingParams {
+            threads: 0,
+            sampling: Sampling::Greedy,
+            beam_size: 5,
+            temperature: 0.0,
+            temperature_increment: 0.2,
+            suppress_blank: true,
+            no_context: true,
+            single_segment: false,
+            max_tokens: 0,
+        };
+        match self {
+            DecodingPreset::Fastest => Some(DecodingParams { temperature_increment: 0.0, single_segment: true, ..balanced }),
+            DecodingPreset::Balanced => Some(balanced),
+            DecodingPreset::Accurate => Some(DecodingParams { sampling: Sampling::BeamSearch, ..balanced }),
+            DecodingPreset::Custom => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_trade_speed_for_accuracy() {
+        let fastest = DecodingPreset::Fastest.params().unwrap();
+        let accurate = DecodingPreset::Accurate.params().unwrap();
+
+        assert_eq!(DecodingParams::default(), DecodingPreset::Balanced.params().unwrap());
+        // No fallback passes and a single segment keep latency predictable
+        assert_eq!(fastest.temperature_increment, 0.0);
+        assert!(fastest.single_segment);
+        assert_eq!(accurate.sampling, Sampling::BeamSearch);
+        assert!(DecodingPreset::Custom.params().is_none());
+
+        // Missing fields keep their defaults
+        let partial: DecodingParams = serde_json::from_str(r#"{ "threads": 8 }"#).unwrap();
+        assert_eq!(partial, DecodingParams { threads: 8, ..Default::default() });
+    }
+}
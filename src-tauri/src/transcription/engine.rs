@@ -1,102 +1,241 @@
-use anyhow::{Result, Context};
-use crossbeam_channel::Receiver;
-use log::{info, error};
-use std::path::Path;
+use anyhow::Result;
+use crossbeam_channel::{Receiver, Sender};
+use log::{info, warn, error};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use whisper_rs::{WhisperContext, FullParams, SamplingStrategy};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
+use super::backend::{Decoded, TranscriptionBackend};
+use super::confidence::{Confidence, ConfidenceFilter};
+use super::context::ContextTracker;
+use super::decoding::DecodingParams;
+use super::filter::{Filtered, TextFilter};
+use super::http::{HttpBackend, HttpBackendConfig};
+use super::prompt::GlossaryTerm;
+use super::whisper::WhisperBackend;
+
+/// After the transcription server fails, the local model is used for this long
+/// before the server is tried again.
+const REMOTE_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Audio handed to the engine by the segmenter.
+pub enum AudioChunk {
+    /// Speech captured so far in an utterance that is still open.
+    Partial(Vec<f32>),
+    /// A finished utterance.
+    Final(Vec<f32>),
+}
+
+/// Text produced by the engine.
+pub enum Transcript {
+    /// Interim text for display only. An empty string clears it.
+    Partial(String),
+    /// Text of a finished utterance, to be typed.
+    /// `language` is the detected language code when the language is set to "auto".
+    Final { text: String, language: Option<String>, confidence: Confidence },
+}
+
+/// Decoding options. Read for every chunk, so changes apply from the next one.
+#[derive(Debug, Clone)]
+pub struct TranscriptionOptions {
+    /// Whisper language code, or "auto" to detect it per utterance.
+    pub language: String,
+    /// Translate into English instead of transcribing.
+    pub translate: bool,
+    /// Spellings to bias Whisper towards through the initial prompt.
+    pub glossary: Vec<GlossaryTerm>,
+    /// Prompt with the tail of the previous transcript.
+    pub carry_context: bool,
+    pub confidence_filter: ConfidenceFilter,
+    /// Whisper sampling, temperature and threading.
+    pub decoding: DecodingParams,
+    /// Noise and hallucination rules, compiled.
+    pub text_filter: Arc<TextFilter>,
+    /// Transcription server to use instead of the local model, when enabled.
+    pub http_backend: HttpBackendConfig,
+}
+
+impl Default for TranscriptionOptions {
+    fn default() -> Self {
+        Self {
+            language: "en".to_string(),
+            translate: false,
+            glossary: Vec::new(),
+            carry_context: false,
+            confidence_filter: ConfidenceFilter::default(),
+            decoding: DecodingParams::default(),
+            text_filter: Arc::new(TextFilter::default()),
+            http_backend: HttpBackendConfig::default(),
+        }
+    }
+}
+
+impl TranscriptionOptions {
+    pub fn detect_language(&self) -> bool {
+        self.language == "auto"
+    }
+}
+
+/// Runs the transcription loop on the local Whisper model, or on a transcription
+/// server when one is configured, falling back to the local model while it fails.
 pub struct TranscriptionEngine {
-    context: WhisperContext,
+    local: WhisperBackend,
+    /// The configured server and the settings it was created from.
+    remote: Option<(HttpBackendConfig, Box<dyn TranscriptionBackend>)>,
+    /// Set while the server is being skipped after a failure.
+    remote_retry_at: Option<Instant>,
 }
 
 impl TranscriptionEngine {
-    pub fn new<P: AsRef<Path>>(model_path: P) -> Result<Self> {
-        let context = WhisperContext::new_with_params(
-            model_path.as_ref().to_str().unwrap(), 
-            whisper_rs::WhisperContextParameters::default()
-        ).context("Failed to load Whisper model")?;
-
-        Ok(Self { context })
+    pub fn new(local: WhisperBackend) -> Self {
+        Self { local, remote: None, remote_retry_at: None }
     }
 
     /// Run the transcription loop.
-    pub fn run(&mut self, rx: Receiver<Vec<f32>>, tx_text: crossbeam_channel::Sender<String>, running: Arc<AtomicBool>) {
+    ///
+    /// A model received on `rx_model` replaces the local one between chunks, so a model
+    /// switch never drops audio: chunks queued while the new model loads are handled by
+    /// the old one, everything after the swap by the new one.
+    pub fn run(
+        &mut self,
+        rx: Receiver<AudioChunk>,
+        rx_model: Receiver<WhisperBackend>,
+        tx_text: Sender<Transcript>,
+        options: Arc<RwLock<TranscriptionOptions>>,
+        context: Arc<Mutex<ContextTracker>>,
+        running: Arc<AtomicBool>,
+    ) {
         info!("Transcription Engine IDLE. Waiting for audio...");
 
-        let mut state = self.context.create_state().expect("failed to create state");
-
         while running.load(Ordering::Relaxed) {
-            // Block until we get a chunk. 
-            // In a real app, we might handle 'is_partial' logic here.
-            // For now, assume each chunk is a "phrase" sent by VAD.
-            if let Ok(audio_data) = rx.recv() {
-                if audio_data.is_empty() { continue; }
-
-                info!("Processing {} samples...", audio_data.len());
-                let t0 = std::time::Instant::now();
-
-                // Configure Params
-                let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-                params.set_print_progress(false);
-                params.set_print_special(false);
-                params.set_print_realtime(false);
-                params.set_print_timestamps(false); // We just want text
-                params.set_language(Some("en"));
-                
-                // Run Inference
-                // Note: full() expects f32, 16kHz
-                if let Err(e) = state.full(params, &audio_data[..]) {
-                     error!("Whisper inference failed: {}", e);
-                     continue;
+            // Block until we get a chunk (or a replacement model)
+            let chunk = crossbeam_channel::select! {
+                recv(rx) -> msg => match msg {
+                    Ok(chunk) => chunk,
+                    // Channel closed
+                    Err(_) => break,
+                },
+                recv(rx_model) -> msg => {
+                    if let Ok(local) = msg {
+                        self.local = local;
+                        info!("🔄 Whisper model swapped in");
+                    }
+                    continue;
                 }
+            };
+            let current_options = options.read().unwrap().clone();
+            self.configure_remote(&current_options.http_backend);
+            let previous_text = current_options
+                .carry_context
+                .then(|| context.lock().unwrap().tail(Instant::now()).map(str::to_string))
+                .flatten();
+            let initial_prompt = self.local.initial_prompt(&current_options.glossary, previous_text.as_deref());
 
-                // Extract Text
-                let num_segments = state.full_n_segments();
-                let mut full_text = String::new();
-                for i in 0..num_segments {
-                    if let Some(segment) = state.get_segment(i) {
-                        if let Ok(text) = segment.to_str() {
-                            full_text.push_str(text);
+            match chunk {
+                AudioChunk::Partial(audio_data) => {
+                    // A newer partial or the final pass is already waiting, this one is stale
+                    if audio_data.is_empty() || !rx.is_empty() { continue; }
+
+                    match self.transcribe(&audio_data, &current_options, initial_prompt.as_deref()) {
+                        Ok(decoded) => {
+                            let rejected = current_options.confidence_filter.rejection(&decoded.confidence).is_some();
+                            if let Filtered::Keep(text) = current_options.text_filter.apply(&decoded.text) {
+                                if !rejected {
+                                    tx_text.send(Transcript::Partial(text)).ok();
+                                }
+                            }
                         }
+                        Err(e) => error!("Whisper inference failed (partial): {}", e),
                     }
                 }
+                AudioChunk::Final(audio_data) => {
+                    if audio_data.is_empty() { continue; }
+
+                    info!("Processing {} samples...", audio_data.len());
+                    let t0 = std::time::Instant::now();
 
-                let dt = t0.elapsed();
-                
-                // --- Hallucination & Noise Filtering ---
-                let mut text = full_text.trim().to_string();
-                
-                // 1. Remove everything in brackets or parentheses (e.g. [BLANK_AUDIO], (upbeat music))
-                // We'll use a simple loop-based removal to avoid regex overhead in the hot path
-                while let Some(start) = text.find(|c| c == '[' || c == '(') {
-                    if let Some(end) = text[start..].find(|c| c == ']' || c == ')') {
-                        let actual_end = start + end + 1;
-                        text.replace_range(start..actual_end, "");
-                    } else {
-                        break;
+                    let decoded = match self.transcribe(&audio_data, &current_options, initial_prompt.as_deref()) {
+                        Ok(decoded) => decoded,
+                        Err(e) => {
+                            error!("Whisper inference failed: {}", e);
+                            tx_text.send(Transcript::Partial(String::new())).ok();
+                            continue;
+                        }
+                    };
+
+                    let dt = t0.elapsed();
+                    let full_text = decoded.text;
+                    let confidence = decoded.confidence;
+                    let filtered = current_options.text_filter.apply(&full_text);
+                    let rejection = match &filtered {
+                        Filtered::Keep(_) => current_options.confidence_filter.rejection(&confidence),
+                        Filtered::Drop(reason) => Some(reason.clone()),
+                    };
+
+                    match (filtered, rejection) {
+                        (Filtered::Keep(text), None) => {
+                            match &decoded.language {
+                                Some(language) => info!("📝 Text ({:?}, {}, {}): {}", dt, language, confidence, text),
+                                None => info!("📝 Text ({:?}, {}): {}", dt, confidence, text),
+                            }
+                            tx_text.send(Transcript::Final { text, language: decoded.language, confidence }).ok();
+                        }
+                        (_, reason) => {
+                            if let (Some(reason), false) = (reason, full_text.trim().is_empty()) {
+                                info!("🗑️ Filtered, {}: '{}'", reason, full_text.trim());
+                            }
+                            // Nothing to type, but interim text shown for this utterance has to go
+                            tx_text.send(Transcript::Partial(String::new())).ok();
+                        }
                     }
                 }
+            }
+        }
+        info!("Transcription Engine stopped.");
+    }
 
-                // 2. Final clean and trim
-                let text = text.trim().to_string();
-                
-                // 3. Filter if empty or just noise tokens
-                if !text.is_empty() 
-                   && text != "..." 
-                   && !text.starts_with("[_") { 
-                    info!("📝 Text ({:?}): {}", dt, text);
-                    tx_text.send(text).ok();
-                } else {
-                     if !full_text.trim().is_empty() {
-                        info!("🗑️ Filtered noise: '{}'", full_text.trim());
-                     }
+    /// Creates, replaces or drops the server backend when its settings changed.
+    fn configure_remote(&mut self, config: &HttpBackendConfig) {
+        if !config.enabled {
+            if self.remote.take().is_some() {
+                info!("🌐 Transcription server disabled, using the local model");
+            }
+            return;
+        }
+        if self.remote.as_ref().is_some_and(|(current, _)| current == config) {
+            return;
+        }
+        self.remote_retry_at = None;
+        self.remote = match HttpBackend::new(config) {
+            Ok(backend) => {
+                info!("🌐 Transcribing on {}", config.url);
+                Some((config.clone(), Box::new(backend)))
+            }
+            Err(e) => {
+                error!("Failed to set up transcription server {}: {:#}", config.url, e);
+                None
+            }
+        };
+    }
+
+    /// Transcribes on the server if there is one, otherwise or if it fails on the local model.
+    fn transcribe(&mut self, audio: &[f32], options: &TranscriptionOptions, initial_prompt: Option<&str>) -> Result<Decoded> {
+        let now = Instant::now();
+        let retry_due = !self.remote_retry_at.is_some_and(|retry_at| now < retry_at);
+        if let Some((_, remote)) = self.remote.as_mut().filter(|_| retry_due) {
+            match remote.transcribe(audio, options, initial_prompt) {
+                Ok(decoded) => {
+                    if self.remote_retry_at.take().is_some() {
+                        info!("🌐 {} is back", remote.name());
+                    }
+                    return Ok(decoded);
+                }
+                Err(e) => {
+                    warn!("🌐 {} failed, using the {} for {:?}: {:#}", remote.name(), self.local.name(), REMOTE_RETRY_INTERVAL, e);
+                    self.remote_retry_at = Some(now + REMOTE_RETRY_INTERVAL);
                 }
-            } else {
-                // Channel closed
-                break;
             }
         }
-        info!("Transcription Engine stopped.");
+        self.local.transcribe(audio, options, initial_prompt)
     }
 }
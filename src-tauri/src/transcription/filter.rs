@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// How a rule's pattern is matched against the transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// The whole utterance, ignoring case, surrounding space and trailing punctuation.
+    Exact,
+    /// A phrase anywhere in the utterance, ignoring case.
+    Contains,
+    /// A regular expression. Prefix with `(?i)` to ignore case.
+    Regex,
+}
+
+/// What happens to an utterance a rule matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+    /// Discard the whole utterance.
+    Drop,
+    /// Remove the matched text.
+    Strip,
+    /// Replace the matched text with the rule's replacement.
+    Replace,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterRule {
+    pub kind: MatchKind,
+    pub pattern: String,
+    pub action: FilterAction,
+    /// Used by `Replace`. Regex rules may reference groups (`$1`).
+    #[serde(default)]
+    pub replacement: String,
+}
+
+impl FilterRule {
+    fn new(kind: MatchKind, pattern: &str, action: FilterAction) -> Self {
+        Self { kind, pattern: pattern.to_string(), action, replacement: String::new() }
+    }
+}
+
+/// The rules that ship with the app: Whisper's non-speech tags and the phrases it
+/// is known to invent from silence and noise.
+pub fn default_rules() -> Vec<FilterRule> {
+    vec![
+        // Non-speech tags, e.g. [BLANK_AUDIO], (upbeat music), [_BEG_]
+        FilterRule::new(MatchKind::Regex, r"\[[^\]]*\]", FilterAction::Strip),
+        FilterRule::new(MatchKind::Regex, r"\([^)]*\)", FilterAction::Strip),
+        FilterRule::new(MatchKind::Regex, r"^\[_", FilterAction::Drop),
+        FilterRule::new(MatchKind::Exact, "...", FilterAction::Drop),
+        // Sign-offs and credits learned from subtitled videos
+        FilterRule::new(MatchKind::Exact, "Thank you for watching", FilterAction::Drop),
+        FilterRule::new(MatchKind::Exact, "Thanks for watching", FilterAction::Drop),
+        FilterRule::new(MatchKind::Exact, "Please subscribe", FilterAction::Drop),
+        FilterRule::new(MatchKind::Contains, "like and subscribe", FilterAction::Drop),
+        FilterRule::new(MatchKind::Contains, "amara.org", FilterAction::Drop),
+    ]
+}
+
+/// Result of running an utterance through the filter.
+#[derive(Debug, PartialEq)]
+pub enum Filtered {
+    Keep(String),
+    /// Dropped, with the reason (the rule that matched, or that nothing was left).
+    Drop(String),
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    rule: FilterRule,
+    regex: Regex,
+}
+
+/// Compiled filter rules, applied in order.
+#[derive(Debug)]
+pub struct TextFilter {
+    rules: Vec<CompiledRule>,
+}
+
+impl TextFilter {
+    /// Compiles `rules`. Fails on an empty pattern or an invalid regex.
+    pub fn new(rules: &[FilterRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                anyhow::ensure!(!rule.pattern.trim().is_empty(), "Filter pattern is empty");
+                let source = match rule.kind {
+                    MatchKind::Exact => format!(r"^\s*{}[\s.!?,]*$", regex::escape(rule.pattern.trim().trim_end_matches(['.', '!', '?', ',']))),
+                    MatchKind::Contains => regex::escape(rule.pattern.trim()),
+                    MatchKind::Regex => rule.pattern.clone(),
+                };
+                let regex = RegexBuilder::new(&source)
+                    .case_insensitive(rule.kind != MatchKind::Regex)
+                    .build()
+                    .with_context(|| format!("Invalid filter pattern '{}'", rule.pattern))?;
+                Ok(CompiledRule { rule: rule.clone(), regex })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn apply(&self, text: &str) -> Filtered {
+        let mut text = text.trim().to_string();
+        for CompiledRule { rule, regex } in &self.rules {
+            if !regex.is_match(&text) {
+                continue;
+            }
+            match rule.action {
+                FilterAction::Drop => return Filtered::Drop(format!("rule '{}'", rule.pattern)),
+                FilterAction::Strip => text = regex.replace_all(&text, "").into_owned(),
+                FilterAction::Replace => text = regex.replace_all(&text, rule.replacement.as_str()).into_owned(),
+            }
+            text = collapse_spaces(&text);
+        }
+
+        // Stripping can leave nothing but stray punctuation behind
+        if !text.chars().any(|c| c.is_alphanumeric()) {
+            return Filtered::Drop("nothing left after filtering".to_string());
+        }
+        Filtered::Keep(text)
+    }
+}
+
+impl Default for TextFilter {
+    fn default() -> Self {
+        Self::new(&default_rules()).expect("default filter rules compile")
+    }
+}
+
+fn collapse_spaces(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keep(text: &str) -> Filtered {
+        Filtered::Keep(text.to_string())
+    }
+
+    #[test]
+    fn test_default_rules_strip_tags_and_drop_hallucinations() {
+        let filter = TextFilter::default();
+
+        assert_eq!(filter.apply(" Hello [BLANK_AUDIO] world "), keep("Hello world"));
+        assert_eq!(filter.apply("(upbeat music) Let's start."), keep("Let's start."));
+        assert!(matches!(filter.apply("[BLANK_AUDIO]"), Filtered::Drop(_)));
+        assert!(matches!(filter.apply("..."), Filtered::Drop(_)));
+        assert!(matches!(filter.apply("[_BEG_"), Filtered::Drop(_)));
+        assert!(matches!(filter.apply("Thank you for watching."), Filtered::Drop(_)));
+        assert!(matches!(filter.apply("thanks for watching!"), Filtered::Drop(_)));
+        assert!(matches!(filter.apply("Subtitles by the Amara.org community"), Filtered::Drop(_)));
+
+        // Exact rules only fire on the whole utterance
+        assert_eq!(filter.apply("Thank you for watching the demo."), keep("Thank you for watching the demo."));
+    }
+
+    #[test]
+    fn test_contains_and_regex_actions() {
+        let rules = vec![
+            FilterRule { replacement: "Kubernetes".to_string(), ..FilterRule::new(MatchKind::Contains, "cooper netties", FilterAction::Replace) },
+            FilterRule::new(MatchKind::Contains, "um", FilterAction::Strip),
+            FilterRule { replacement: "v$1".to_string(), ..FilterRule::new(MatchKind::Regex, r"version (\d+)", FilterAction::Replace) },
+        ];
+        let filter = TextFilter::new(&rules).unwrap();
+
+        assert_eq!(filter.apply("Deploy Cooper Netties um version 2"), keep("Deploy Kubernetes v2"));
+        // Regex rules are case-sensitive unless they opt out
+        assert_eq!(filter.apply("Version 3"), keep("Version 3"));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        assert!(TextFilter::new(&[FilterRule::new(MatchKind::Regex, "(unclosed", FilterAction::Drop)]).is_err());
+        assert!(TextFilter::new(&[FilterRule::new(MatchKind::Contains, "  ", FilterAction::Drop)]).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use anyhow::{Context, Result};
+use reqwest::blocking::{multipart, Client};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::time::Duration;
+
+use super::backend::{Decoded, TranscriptionBackend};
+use super::confidence::{Confidence, SegmentScore};
+use super::engine::TranscriptionOptions;
+
+/// Connecting should be quick on a LAN. An unreachable server fails fast rather than
+/// using up the whole request timeout.
+const MAX_CONNECT_TIMEOUT_MS: u64 = 2000;
+
+/// Transcription server speaking the OpenAI `/v1/audio/transcriptions` protocol,
+/// e.g. faster-whisper-server or the whisper.cpp server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpBackendConfig {
+    pub enabled: bool,
+    /// Base URL such as "http://192.168.1.20:8000". The `/v1/audio/...` path is appended.
+    pub url: String,
+    /// Model name sent with every request. Servers hosting a single model ignore it.
+    pub model: String,
+    /// Time allowed for a whole request before falling back to the local model.
+    pub timeout_ms: u64,
+}
+
+impl Default for HttpBackendConfig {
+    fn default() -> Self {
+        Self { enabled: false, url: "http://localhost:8000".to_string(), model: "whisper-1".to_string(), timeout_ms: 5000 }
+    }
+}
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+    /// Language code or English name, depending on the server.
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<SegmentResponse>,
+}
+
+#[derive(Deserialize)]
+struct SegmentResponse {
+    avg_logprob: Option<f32>,
+    no_speech_prob: Option<f32>,
+}
+
+pub struct HttpBackend {
+    client: Client,
+    url: String,
+    model: String,
+}
+
+impl HttpBackend {
+    pub fn new(config: &HttpBackendConfig) -> Result<Self> {
+        let client = Client::builder()
+            .connect_timeout(Duration::from_millis(config.timeout_ms.min(MAX_CONNECT_TIMEOUT_MS)))
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self { client, url: config.url.trim_end_matches('/').to_string(), model: config.model.clone() })
+    }
+}
+
+impl TranscriptionBackend for HttpBackend {
+    fn name(&self) -> &'static str {
+        "transcription server"
+    }
+
+    fn transcribe(&mut self, audio: &[f32], options: &TranscriptionOptions, initial_prompt: Option<&str>) -> Result<Decoded> {
+        let task = if options.translate { "translations" } else { "transcriptions" };
+        let file = multipart::Part::bytes(encode_wav(audio)?).file_name("audio.wav").mime_str("audio/wav")?;
+        let mut form = multipart::Form::new()
+            .part("file", file)
+            .text("model", self.model.clone())
+            // verbose_json carries the detected language and per-segment scores
+            .text("response_format", "verbose_json")
+            .text("temperature", options.decoding.temperature.to_string());
+        if !options.detect_language() && !options.translate {
+            form = form.text("language", options.language.clone());
+        }
+        if let Some(initial_prompt) = initial_prompt {
+            form = form.text("prompt", initial_prompt.to_string());
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/v1/audio/{}", self.url, task))
+            .multipart(form)
+            .send()
+            .with_context(|| format!("Request to {} failed", self.url))?
+            .error_for_status()?;
+        let body = response.text().context("Failed to read server response")?;
+        let parsed: TranscriptionResponse = serde_json::from_str(&body).context("Unexpected server response")?;
+
+        let language = parsed.language.filter(|_| options.detect_language()).and_then(|language| language_code(&language));
+        Ok(Decoded { text: parsed.text, language, confidence: confidence(&parsed.segments) })
+    }
+}
+
+/// Segment scores as reported by the server. Scores it leaves out stay unscored.
+fn confidence(segments: &[SegmentResponse]) -> Confidence {
+    let scores: Vec<SegmentScore> = segments
+        .iter()
+        .map(|segment| SegmentScore {
+            no_speech_probability: segment.no_speech_prob,
+            token_probabilities: segment.avg_logprob.map(f32::exp).into_iter().collect(),
+        })
+        .collect();
+    Confidence::from_segments(&scores)
+}
+
+/// OpenAI-style servers answer "english", whisper.cpp answers "en". Whisper knows both.
+fn language_code(language: &str) -> Option<String> {
+    let language = language.trim().to_lowercase();
+    if language.is_empty() || language.contains('\0') {
+        return None;
+    }
+    whisper_rs::get_lang_id(&language).and_then(whisper_rs::get_lang_str).map(str::to_string)
+}
+
+/// 16-bit PCM WAV, which every server accepts.
+fn encode_wav(audio: &[f32]) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut buffer = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut buffer, spec)?;
+    for &sample in audio {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(buffer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves one request with `status` and `body`, handing back the raw request.
+    fn stand_in_server(status: &'static str, body: &'static str, delay: Duration) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                head.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let length = head
+                .lines()
+                .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                .unwrap_or(0);
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            thread::sleep(delay);
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            reader.get_mut().write_all(response.as_bytes()).ok();
+            head + &String::from_utf8_lossy(&request_body)
+        });
+        (url, handle)
+    }
+
+    fn backend(url: &str, timeout_ms: u64) -> HttpBackend {
+        HttpBackend::new(&HttpBackendConfig { enabled: true, url: format!("{}/", url), model: "small.en".to_string(), timeout_ms }).unwrap()
+    }
+
+    #[test]
+    fn test_posts_multipart_and_reads_verbose_json() {
+        let (url, server) = stand_in_server(
+            "200 OK",
+            r#"{"text":" Ship it.","segments":[{"avg_logprob":-0.1,"no_speech_prob":0.02},{"avg_logprob":-0.3,"no_speech_prob":0.1}]}"#,
+            Duration::ZERO,
+        );
+        let options = TranscriptionOptions { language: "de".to_string(), ..Default::default() };
+        let decoded = backend(&url, 5000).transcribe(&[0.0; 1600], &options, Some("Glossary: FlowType")).unwrap();
+
+        assert_eq!(decoded.text, " Ship it.");
+        assert!(decoded.language.is_none());
+        let expected = ((-0.1f32).exp() + (-0.3f32).exp()) / 2.0;
+        assert!((decoded.confidence.probability.unwrap() - expected).abs() < 1e-5);
+        assert_eq!(decoded.confidence.no_speech_probability, Some(0.1));
+        assert_eq!(decoded.confidence.segments.len(), 2);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /v1/audio/transcriptions HTTP/1.1"));
+        for field in ["file", "model", "response_format", "language", "prompt"] {
+            assert!(request.contains(&format!("name=\"{}\"", field)), "missing {}", field);
+        }
+        assert!(request.contains("small.en") && request.contains("Glossary: FlowType") && request.contains("RIFF"));
+    }
+
+    #[test]
+    fn test_server_errors_and_timeouts_fail() {
+        let (url, server) = stand_in_server("500 Internal Server Error", "{}", Duration::ZERO);
+        assert!(backend(&url, 5000).transcribe(&[0.0; 160], &TranscriptionOptions::default(), None).is_err());
+        server.join().unwrap();
+
+        let (url, server) = stand_in_server("200 OK", r#"{"text":"late"}"#, Duration::from_millis(500));
+        assert!(backend(&url, 100).transcribe(&[0.0; 160], &TranscriptionOptions::default(), None).is_err());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_missing_scores_are_unscored() {
+        assert_eq!(confidence(&[]), Confidence { probability: None, no_speech_probability: None, segments: Vec::new() });
+        let segments = [SegmentResponse { avg_logprob: None, no_speech_prob: Some(0.8) }];
+        assert_eq!(confidence(&segments).probability, None);
+        assert_eq!(confidence(&segments).no_speech_probability, Some(0.8));
+    }
+}
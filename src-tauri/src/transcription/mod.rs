@@ -1,6 +1,15 @@
+pub mod backend;
+pub mod confidence;
+pub mod context;
+pub mod decoding;
 pub mod engine;
+pub mod filter;
+pub mod http;
+pub mod prompt;
+pub mod whisper;
 
 
 
 // The logic will reside in engine.rs
-pub use engine::TranscriptionEngine;
+pub use engine::{AudioChunk, Transcript, TranscriptionEngine, TranscriptionOptions};
+pub use whisper::WhisperBackend;
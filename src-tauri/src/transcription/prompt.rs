@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+/// Lead-in for the glossary. Whisper treats the initial prompt as preceding text,
+/// so a short list nudges it towards these spellings.
+const GLOSSARY_PREFIX: &str = "Glossary:";
+const SEPARATOR: &str = ",";
+
+/// A word or phrase Whisper should spell as written: product names, acronyms, people.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    pub term: String,
+    /// Unix seconds when the term was added or last heard in a transcription.
+    pub last_used: u64,
+}
+
+/// Builds the initial prompt from the glossary, most recently used terms first,
+/// stopping before `max_tokens` would be exceeded. `count_tokens` measures text with
+/// the model's tokenizer.
+pub fn build_prompt(terms: &[GlossaryTerm], max_tokens: usize, count_tokens: impl Fn(&str) -> usize) -> Option<String> {
+    let mut by_recency: Vec<&GlossaryTerm> = terms.iter().filter(|t| !t.term.trim().is_empty()).collect();
+    by_recency.sort_by_key(|t| std::cmp::Reverse(t.last_used));
+
+    let mut used = count_tokens(GLOSSARY_PREFIX);
+    let mut prompt = GLOSSARY_PREFIX.to_string();
+    let mut added = 0;
+    for term in by_recency {
+        let piece = if added == 0 {
+            format!(" {}", term.term.trim())
+        } else {
+            format!("{} {}", SEPARATOR, term.term.trim())
+        };
+        // Pieces are measured on their own. A term that doesn't fit is skipped,
+        // so a shorter, older one can still take the remaining room.
+        let cost = count_tokens(&piece);
+        if used + cost > max_tokens {
+            continue;
+        }
+        used += cost;
+        prompt.push_str(&piece);
+        added += 1;
+    }
+    (added > 0).then_some(prompt)
+}
+
+/// Combines the glossary with the previous transcript. The transcript goes last,
+/// so it reads as the text directly preceding the new audio.
+pub fn join_prompt(glossary: Option<String>, context: Option<&str>) -> Option<String> {
+    match (glossary, context) {
+        (Some(glossary), Some(context)) => Some(format!("{}. {}", glossary, context)),
+        (Some(glossary), None) => Some(glossary),
+        (None, context) => context.map(str::to_string),
+    }
+}
+
+/// Refreshes `last_used` for every term that appears in `text` as whole words,
+/// ignoring case. Returns true if any term was found.
+pub fn mark_used(terms: &mut [GlossaryTerm], text: &str, now: u64) -> bool {
+    let haystack = text.to_lowercase();
+    let mut found = false;
+    for term in terms.iter_mut() {
+        if contains_phrase(&haystack, &term.term.trim().to_lowercase()) {
+            term.last_used = now;
+            found = true;
+        }
+    }
+    found
+}
+
+fn contains_phrase(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return false;
+    }
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !is_word(before) && !is_word(after)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(term: &str, last_used: u64) -> GlossaryTerm {
+        GlossaryTerm { term: term.to_string(), last_used }
+    }
+
+    // One token per word, punctuation is free
+    fn words(text: &str) -> usize {
+        text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).count()
+    }
+
+    #[test]
+    fn test_prompt_prefers_recent_terms_within_limit() {
+        let terms = vec![term("Kubernetes", 10), term("FlowType", 30), term("Anya Petrova", 20), term("GRPC", 5)];
+
+        assert_eq!(build_prompt(&terms, 100, words).unwrap(), "Glossary: FlowType, Anya Petrova, Kubernetes, GRPC");
+        // Prefix (1) + FlowType (1) + Anya Petrova (2) leaves no room for the rest
+        assert_eq!(build_prompt(&terms, 4, words).unwrap(), "Glossary: FlowType, Anya Petrova");
+        // A two-word name that doesn't fit is skipped for a shorter, older term
+        assert_eq!(build_prompt(&terms, 3, words).unwrap(), "Glossary: FlowType, Kubernetes");
+        assert!(build_prompt(&terms, 1, words).is_none());
+        assert!(build_prompt(&[], 100, words).is_none());
+    }
+
+    #[test]
+    fn test_context_follows_glossary() {
+        assert_eq!(
+            join_prompt(Some("Glossary: FlowType".to_string()), Some("we shipped it")).unwrap(),
+            "Glossary: FlowType. we shipped it"
+        );
+        assert_eq!(join_prompt(None, Some("we shipped it")).unwrap(), "we shipped it");
+        assert!(join_prompt(None, None).is_none());
+    }
+
+    #[test]
+    fn test_mark_used_matches_whole_words() {
+        let mut terms = vec![term("FlowType", 1), term("API", 1), term("Anya Petrova", 1)];
+
+        assert!(mark_used(&mut terms, "Ask anya petrova about the rapid flowtype release.", 50));
+        assert_eq!(terms[0].last_used, 50);
+        assert_eq!(terms[2].last_used, 50);
+        // "rapid" contains "api" but isn't the acronym
+        assert_eq!(terms[1].last_used, 1);
+
+        assert!(!mark_used(&mut terms, "Nothing relevant here", 60));
+    }
+}
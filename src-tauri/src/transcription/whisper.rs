@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperState};
+
+use super::backend::{Decoded, TranscriptionBackend};
+use super::confidence::{Confidence, SegmentScore};
+use super::decoding::{DecodingParams, Sampling};
+use super::engine::TranscriptionOptions;
+use super::prompt::{self, GlossaryTerm};
+
+/// Upper bound when measuring a single glossary term.
+const MAX_TERM_TOKENS: usize = 64;
+/// One second of silence at 16kHz for the warm-up pass.
+const WARM_UP_SAMPLES: usize = 16000;
+
+/// A Whisper model loaded in-process. The decoding state is created once and reused
+/// for every utterance.
+pub struct WhisperBackend {
+    context: WhisperContext,
+    state: WhisperState,
+}
+
+impl WhisperBackend {
+    pub fn new<P: AsRef<Path>>(model_path: P) -> Result<Self> {
+        let context = WhisperContext::new_with_params(
+            model_path.as_ref().to_str().unwrap(),
+            whisper_rs::WhisperContextParameters::default()
+        ).context("Failed to load Whisper model")?;
+        let state = context.create_state().context("Failed to create Whisper state")?;
+
+        Ok(Self { context, state })
+    }
+
+    /// Runs one pass over silence, so the first real utterance doesn't pay for
+    /// whisper.cpp's buffer allocations.
+    pub fn warm_up(&mut self) -> Result<()> {
+        self.transcribe(&[0.0; WARM_UP_SAMPLES], &TranscriptionOptions::default(), None)
+            .context("Warm-up pass failed")?;
+        Ok(())
+    }
+
+    /// Glossary and previous transcript as an initial prompt that fits the model's prompt
+    /// budget (half the text context, as whisper.cpp keeps no more than that). The glossary
+    /// gets whatever the previous transcript leaves.
+    pub fn initial_prompt(&self, glossary: &[GlossaryTerm], previous_text: Option<&str>) -> Option<String> {
+        let max_tokens = (self.context.n_text_ctx() / 2).max(0) as usize;
+        let previous_tokens = previous_text
+            .and_then(|text| self.context.tokenize(text, max_tokens).ok())
+            .map_or(0, |tokens| tokens.len());
+        let glossary = if glossary.is_empty() {
+            None
+        } else {
+            // A term the tokenizer rejects counts as too long to fit
+            prompt::build_prompt(glossary, max_tokens.saturating_sub(previous_tokens), |text| {
+                self.context.tokenize(text, MAX_TERM_TOKENS).map_or(usize::MAX / 2, |tokens| tokens.len())
+            })
+        };
+        prompt::join_prompt(glossary, previous_text)
+    }
+}
+
+impl TranscriptionBackend for WhisperBackend {
+    fn name(&self) -> &'static str {
+        "local model"
+    }
+
+    /// Runs Whisper over 16kHz mono audio and returns the concatenated segment text.
+    fn transcribe(&mut self, audio_data: &[f32], options: &TranscriptionOptions, initial_prompt: Option<&str>) -> Result<Decoded> {
+        // Configure Params
+        let decoding = &options.decoding;
+        let mut params = FullParams::new(sampling_strategy(decoding));
+        params.set_print_progress(false);
+        params.set_print_special(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false); // We just want text
+        // "auto" makes Whisper detect the language
+        params.set_language(Some(&options.language));
+        params.set_translate(options.translate);
+        if let Some(initial_prompt) = initial_prompt {
+            params.set_initial_prompt(initial_prompt);
+        }
+        if decoding.threads > 0 {
+            params.set_n_threads(decoding.threads as i32);
+        }
+        params.set_temperature(decoding.temperature);
+        params.set_temperature_inc(decoding.temperature_increment);
+        params.set_suppress_blank(decoding.suppress_blank);
+        params.set_no_context(decoding.no_context);
+        params.set_single_segment(decoding.single_segment);
+        params.set_max_tokens(decoding.max_tokens as i32);
+
+        // Run Inference
+        // Note: full() expects f32, 16kHz
+        let state = &mut self.state;
+        state.full(params, audio_data)?;
+
+        // Extract Text, with the probabilities of every text token
+        let num_segments = state.full_n_segments();
+        let first_special = self.context.token_eot();
+        let mut full_text = String::new();
+        let mut scores = Vec::with_capacity(num_segments.max(0) as usize);
+        for i in 0..num_segments {
+            if let Some(segment) = state.get_segment(i) {
+                if let Ok(text) = segment.to_str() {
+                    full_text.push_str(text);
+                }
+                // Timestamps, language and task markers all sit at or above end-of-text
+                let token_probabilities = (0..segment.n_tokens())
+                    .filter_map(|t| segment.get_token(t))
+                    .filter(|token| token.token_id() < first_special)
+                    .map(|token| token.token_probability())
+                    .collect();
+                scores.push(SegmentScore { no_speech_probability: Some(segment.no_speech_probability()), token_probabilities });
+            }
+        }
+        let language = options
+            .detect_language()
+            .then(|| whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(str::to_string))
+            .flatten();
+        Ok(Decoded { text: full_text, language, confidence: Confidence::from_segments(&scores) })
+    }
+}
+
+fn sampling_strategy(decoding: &DecodingParams) -> SamplingStrategy {
+    match decoding.sampling {
+        Sampling::Greedy => SamplingStrategy::Greedy { best_of: 1 },
+        // Patience isn't implemented by whisper.cpp, -1 leaves it off
+        Sampling::BeamSearch => SamplingStrategy::BeamSearch { beam_size: decoding.beam_size as i32, patience: -1.0 },
+    }
+}
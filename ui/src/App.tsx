@@ -2,30 +2,212 @@ import { useEffect, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
 import "./App.css";
 
+type VadBackend = "energy" | "spectral" | "silero";
+type ActivationMode = "vad" | "push_to_talk" | "toggle";
+
 interface VadPayload {
   state: "speaking" | "silence";
   rms: number;
+  backend: VadBackend;
+}
+
+interface VadThresholds {
+  noise_floor: number;
+  start_threshold: number;
+  stop_threshold: number;
+}
+
+interface VadThresholdsPayload extends VadThresholds {
+  adaptive: boolean;
+}
+
+interface VadCalibrationPayload {
+  status: "started" | "finished";
+  thresholds: VadThresholds | null;
+}
+
+interface VadTuning {
+  start_window_ms: number;
+  pre_roll_ms: number;
+  post_roll_ms: number;
+}
+
+interface EngineStatusPayload {
+  status: "loading" | "ready" | "error";
+  model: string | null;
+  error: string | null;
+}
+
+interface ModelStatusPayload {
+  model: string;
+  status: "loading" | "ready" | "error";
+  error: string | null;
+}
+
+const WHISPER_MODELS = [
+  "tiny.en",
+  "tiny",
+  "base.en",
+  "base",
+  "small.en",
+  "small",
+  "medium.en",
+  "medium",
+  "large-v3-turbo",
+  "distil-large-v3",
+];
+
+const LANGUAGES: [string, string][] = [
+  ["auto", "Auto-detect"],
+  ["en", "English"],
+  ["es", "Spanish"],
+  ["fr", "French"],
+  ["de", "German"],
+  ["it", "Italian"],
+  ["pt", "Portuguese"],
+  ["nl", "Dutch"],
+  ["pl", "Polish"],
+  ["ru", "Russian"],
+  ["uk", "Ukrainian"],
+  ["tr", "Turkish"],
+  ["ar", "Arabic"],
+  ["hi", "Hindi"],
+  ["zh", "Chinese"],
+  ["ja", "Japanese"],
+  ["ko", "Korean"],
+];
+
+interface SegmentConfidence {
+  probability: number | null;
+  no_speech_probability: number | null;
+}
+
+// Scores are null when the transcription server reported none
+interface Confidence {
+  probability: number | null;
+  no_speech_probability: number | null;
+  segments: SegmentConfidence[];
+}
+
+type DecodingPreset = "fastest" | "balanced" | "accurate" | "custom";
+
+interface DecodingParams {
+  threads: number;
+  sampling: "greedy" | "beam_search";
+  beam_size: number;
+  temperature: number;
+  temperature_increment: number;
+  suppress_blank: boolean;
+  no_context: boolean;
+  single_segment: boolean;
+  max_tokens: number;
+}
+
+interface HttpBackendConfig {
+  enabled: boolean;
+  url: string;
+  model: string;
+  timeout_ms: number;
+}
+
+interface ItnOptions {
+  numbers: boolean;
+  ordinals: boolean;
+  percentages: boolean;
+  times: boolean;
+  dates: boolean;
+  currency: boolean;
+  units: boolean;
+}
+
+const ITN_CATEGORIES: { key: keyof ItnOptions; label: string }[] = [
+  { key: "numbers", label: "Numbers (23)" },
+  { key: "ordinals", label: "Ordinals (21st)" },
+  { key: "percentages", label: "Percentages (5%)" },
+  { key: "times", label: "Times (5:30 PM)" },
+  { key: "dates", label: "Dates (March 5, 2024)" },
+  { key: "currency", label: "Currency ($10.50)" },
+  { key: "units", label: "Units (5 km)" },
+];
+
+type DisfluencyMode = "off" | "conservative" | "aggressive";
+
+interface DisfluencyConfig {
+  mode: DisfluencyMode;
+  fillers: string[];
+}
+
+interface ConfidenceFilter {
+  enabled: boolean;
+  min_probability: number;
+  max_no_speech_probability: number;
 }
 
 interface TranscriptionPayload {
   text: string;
+  language: string | null;
+  confidence: Confidence | null;
+}
+
+interface AudioMetricsPayload {
+  dropped_samples: number;
+  dropped_since_last: number;
+}
+
+interface AudioDevicePayload {
+  device: string | null;
+  status: "connected" | "reconnecting";
 }
 
+type ChannelMix =
+  | { mode: "average" }
+  | { mode: "single"; channel: number }
+  | { mode: "weighted"; weights: number[] };
+
 interface AppSettings {
   auto_space: boolean;
   silence_timeout: number;
   allow_commands: boolean;
   disable_punctuation: boolean;
+  spoken_punctuation: boolean;
   shortcuts: Record<string, string>;
+  input_device: string | null;
+  channel_mix: ChannelMix;
+  resample_quality: "fast" | "high";
+  prefer_native_rate: boolean;
+  vad_backend: VadBackend;
+  adaptive_vad: boolean;
+  vad_thresholds: VadThresholds;
+  vad_tuning: VadTuning;
+  model: string;
+  language: string;
+  translate: boolean;
+  glossary: GlossaryTerm[];
+  carry_context: boolean;
+  context_idle_reset_ms: number;
+  confidence_filter: ConfidenceFilter;
+  decoding_preset: DecodingPreset;
+  decoding: DecodingParams;
+  filter_rules: FilterRule[];
+  http_backend: HttpBackendConfig;
+  itn: ItnOptions;
+  disfluency: DisfluencyConfig;
+  max_utterance_ms: number;
+  activation_mode: ActivationMode;
+  activation_hotkey: string;
 }
 
 import { invoke } from "@tauri-apps/api/core";
 import { enable, disable, isEnabled } from "@tauri-apps/plugin-autostart";
 import { ShortcutsModal } from "./ShortcutsModal";
+import { GlossaryModal, type GlossaryTerm } from "./GlossaryModal";
+import { FilterRulesModal, type FilterRule } from "./FilterRulesModal";
 
 function App() {
   const [vadState, setVadState] = useState<"speaking" | "silence">("silence");
   const [lastText, setLastText] = useState("");
+  const [partialText, setPartialText] = useState("");
+  const [engineStatus, setEngineStatus] = useState<EngineStatusPayload>({ status: "loading", model: null, error: null });
   const [history, setHistory] = useState<string[]>([]);
 
   // Settings State
@@ -34,10 +216,68 @@ function App() {
   const [autostart, setAutostart] = useState(false);
   const [allowCommands, setAllowCommands] = useState(true);
   const [disablePunctuation, setDisablePunctuation] = useState(false);
+  const [spokenPunctuation, setSpokenPunctuation] = useState(false);
+  const [disfluency, setDisfluency] = useState<DisfluencyConfig>({ mode: "off", fillers: [] });
+  const [fillersText, setFillersText] = useState("");
+  const [disfluencyError, setDisfluencyError] = useState<string | null>(null);
+  const [itn, setItn] = useState<ItnOptions>({
+    numbers: true,
+    ordinals: true,
+    percentages: true,
+    times: true,
+    dates: true,
+    currency: true,
+    units: true,
+  });
   const [shortcuts, setShortcuts] = useState<Record<string, string>>({});
+  const [inputDevice, setInputDevice] = useState<string | null>(null);
+  const [inputDevices, setInputDevices] = useState<string[]>([]);
+  const [channelMix, setChannelMix] = useState<ChannelMix>({ mode: "average" });
+  const [channelWeights, setChannelWeights] = useState("");
+  const [audioDevice, setAudioDevice] = useState<AudioDevicePayload | null>(null);
+  const [droppedSamples, setDroppedSamples] = useState(0);
+  const [resampleQuality, setResampleQuality] = useState<"fast" | "high">("fast");
+  const [preferNativeRate, setPreferNativeRate] = useState(false);
+  const [vadBackend, setVadBackend] = useState<VadBackend>("energy");
+  const [adaptiveVad, setAdaptiveVad] = useState(false);
+  const [vadThresholds, setVadThresholds] = useState<VadThresholds | null>(null);
+  const [calibrating, setCalibrating] = useState(false);
+  const [vadTuning, setVadTuning] = useState<VadTuning>({ start_window_ms: 300, pre_roll_ms: 500, post_roll_ms: 0 });
+  const [tuningError, setTuningError] = useState<string | null>(null);
+  const [maxUtterance, setMaxUtterance] = useState(15000);
+  const [model, setModel] = useState("tiny.en");
+  const [modelStatus, setModelStatus] = useState<ModelStatusPayload | null>(null);
+  const [language, setLanguage] = useState("en");
+  const [translate, setTranslate] = useState(false);
+  const [detectedLanguage, setDetectedLanguage] = useState<string | null>(null);
+  const [activationMode, setActivationMode] = useState<ActivationMode>("vad");
+  const [activationHotkey, setActivationHotkey] = useState("");
+  const [hotkeyError, setHotkeyError] = useState<string | null>(null);
 
   const [settingsOpen, setSettingsOpen] = useState(false);
   const [shortcutsOpen, setShortcutsOpen] = useState(false);
+  const [glossary, setGlossary] = useState<GlossaryTerm[]>([]);
+  const [glossaryOpen, setGlossaryOpen] = useState(false);
+  const [filterRules, setFilterRules] = useState<FilterRule[]>([]);
+  const [filterRulesOpen, setFilterRulesOpen] = useState(false);
+  const [carryContext, setCarryContext] = useState(false);
+  const [contextIdleReset, setContextIdleReset] = useState(30000);
+  const [confidenceFilter, setConfidenceFilter] = useState<ConfidenceFilter>({
+    enabled: true,
+    min_probability: 0.4,
+    max_no_speech_probability: 0.6,
+  });
+  const [decodingPreset, setDecodingPreset] = useState<DecodingPreset>("balanced");
+  const [decoding, setDecoding] = useState<DecodingParams | null>(null);
+  const [decodingError, setDecodingError] = useState<string | null>(null);
+  const [httpBackend, setHttpBackend] = useState<HttpBackendConfig>({
+    enabled: false,
+    url: "http://localhost:8000",
+    model: "whisper-1",
+    timeout_ms: 5000,
+  });
+  const [httpBackendError, setHttpBackendError] = useState<string | null>(null);
+  const [lastConfidence, setLastConfidence] = useState<Confidence | null>(null);
 
   const minimize = () => {
     invoke("minimize_window");
@@ -61,10 +301,209 @@ function App() {
     invoke("set_disable_punctuation", { state: newVal });
   };
 
+  const handleToggleSpokenPunctuation = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const newVal = e.target.checked;
+    setSpokenPunctuation(newVal);
+    invoke("set_spoken_punctuation", { state: newVal });
+  };
+
+  const handleInputDeviceChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const newVal = e.target.value || null;
+    setInputDevice(newVal);
+    invoke("set_input_device", { name: newVal });
+  };
+
+  const applyChannelMix = (mix: ChannelMix) => {
+    setChannelMix(mix);
+    invoke("set_channel_mix", { mix });
+  };
+
+  const handleChannelModeChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const mode = e.target.value;
+    if (mode === "single") {
+      applyChannelMix({ mode: "single", channel: 0 });
+    } else if (mode === "weighted") {
+      setChannelWeights("1, 1");
+      applyChannelMix({ mode: "weighted", weights: [1, 1] });
+    } else {
+      applyChannelMix({ mode: "average" });
+    }
+  };
+
+  // Weights are typed as a comma-separated list, one per channel
+  const handleChannelWeightsCommit = () => {
+    const weights = channelWeights
+      .split(",")
+      .map((w) => parseFloat(w))
+      .filter((w) => Number.isFinite(w) && w >= 0);
+    setChannelWeights(weights.join(", "));
+    applyChannelMix({ mode: "weighted", weights });
+  };
+
+  const handleResampleQualityChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const newVal = e.target.value as "fast" | "high";
+    setResampleQuality(newVal);
+    invoke("set_resample_quality", { quality: newVal });
+  };
+
+  const handleToggleNativeRate = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const newVal = e.target.checked;
+    setPreferNativeRate(newVal);
+    invoke("set_prefer_native_rate", { state: newVal });
+  };
+
+  const handleVadBackendChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const newVal = e.target.value as VadBackend;
+    setVadBackend(newVal);
+    invoke("set_vad_backend", { backend: newVal });
+  };
+
+  const handleToggleAdaptiveVad = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const newVal = e.target.checked;
+    setAdaptiveVad(newVal);
+    invoke("set_adaptive_vad", { state: newVal });
+  };
+
+  const handleCalibrate = () => {
+    setCalibrating(true);
+    invoke("calibrate_vad", {}).catch((err) => {
+      console.error("Calibration error:", err);
+      setCalibrating(false);
+    });
+  };
+
+  const handleThresholdChange = (key: "start_threshold" | "stop_threshold", value: number) => {
+    if (!vadThresholds || isNaN(value)) return;
+    const next = { ...vadThresholds, [key]: value };
+    setVadThresholds(next);
+    setTuningError(null);
+    invoke("set_vad_thresholds", { startThreshold: next.start_threshold, stopThreshold: next.stop_threshold })
+      .catch((err) => setTuningError(String(err)));
+  };
+
+  const handleTuningChange = (key: keyof VadTuning, value: number) => {
+    if (isNaN(value)) return;
+    const next = { ...vadTuning, [key]: value };
+    setVadTuning(next);
+    setTuningError(null);
+    invoke("set_vad_tuning", { tuning: next }).catch((err) => setTuningError(String(err)));
+  };
+
+  const handleResetTuning = () => {
+    setTuningError(null);
+    invoke<AppSettings>("reset_vad_tuning").then((settings) => {
+      setSilenceTimeout(settings.silence_timeout);
+      setVadThresholds(settings.vad_thresholds);
+      setVadTuning(settings.vad_tuning);
+    });
+  };
+
+  const handleActivationModeChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const newVal = e.target.value as ActivationMode;
+    setActivationMode(newVal);
+    setHotkeyError(null);
+    invoke("set_activation_mode", { mode: newVal }).catch((err) => setHotkeyError(String(err)));
+  };
+
+  const handleHotkeyCommit = () => {
+    setHotkeyError(null);
+    invoke("set_activation_hotkey", { hotkey: activationHotkey }).catch((err) => setHotkeyError(String(err)));
+  };
+
+  const handleModelChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const newVal = e.target.value;
+    setModel(newVal);
+    invoke("set_model", { model: newVal }).catch((err) =>
+      setModelStatus({ model: newVal, status: "error", error: String(err) })
+    );
+  };
+
+  const handleLanguageChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const newVal = e.target.value;
+    setLanguage(newVal);
+    setDetectedLanguage(null);
+    invoke("set_language", { language: newVal });
+  };
+
+  const handleToggleTranslate = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const newVal = e.target.checked;
+    setTranslate(newVal);
+    invoke("set_translate", { state: newVal });
+  };
+
+  const handleToggleCarryContext = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const newVal = e.target.checked;
+    setCarryContext(newVal);
+    invoke("set_carry_context", { state: newVal });
+  };
+
+  const handleContextIdleResetChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const val = parseInt(e.target.value);
+    setContextIdleReset(val);
+    invoke("set_context_idle_reset", { ms: val });
+  };
+
+  const handleDisfluencyChange = (changes: Partial<DisfluencyConfig>) => {
+    const next = { ...disfluency, ...changes };
+    setDisfluency(next);
+    setDisfluencyError(null);
+    invoke("set_disfluency", { config: next }).catch((err) => setDisfluencyError(String(err)));
+  };
+
+  const commitFillers = () => {
+    const fillers = fillersText.split(",").map((f) => f.trim()).filter((f) => f.length > 0);
+    setFillersText(fillers.join(", "));
+    handleDisfluencyChange({ fillers });
+  };
+
+  const handleItnChange = (changes: Partial<ItnOptions>) => {
+    const next = { ...itn, ...changes };
+    setItn(next);
+    invoke("set_itn", { options: next });
+  };
+
+  const handleConfidenceFilterChange = (changes: Partial<ConfidenceFilter>) => {
+    const next = { ...confidenceFilter, ...changes };
+    setConfidenceFilter(next);
+    invoke("set_confidence_filter", { filter: next });
+  };
+
+  const handleDecodingPresetChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const newVal = e.target.value as DecodingPreset;
+    setDecodingPreset(newVal);
+    setDecodingError(null);
+    if (newVal === "custom") return;
+    invoke<DecodingParams>("set_decoding_preset", { preset: newVal })
+      .then(setDecoding)
+      .catch((err) => setDecodingError(String(err)));
+  };
+
+  const handleDecodingChange = (changes: Partial<DecodingParams>) => {
+    if (!decoding || Object.values(changes).some((v) => typeof v === "number" && isNaN(v))) return;
+    const next = { ...decoding, ...changes };
+    setDecoding(next);
+    setDecodingPreset("custom");
+    setDecodingError(null);
+    invoke("set_decoding", { params: next }).catch((err) => setDecodingError(String(err)));
+  };
+
+  const handleHttpBackendChange = (changes: Partial<HttpBackendConfig>) => {
+    const next = { ...httpBackend, ...changes };
+    setHttpBackend(next);
+    setHttpBackendError(null);
+    invoke("set_http_backend", { config: next }).catch((err) => setHttpBackendError(String(err)));
+  };
+
+  const handleMaxUtteranceChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
+    const val = parseInt(e.target.value);
+    setMaxUtterance(val);
+    invoke("set_max_utterance", { ms: val }).catch((err) => setTuningError(String(err)));
+  };
+
   const handleTimeoutChange = (e: React.ChangeEvent<HTMLInputElement>) => {
     const val = parseInt(e.target.value);
     setSilenceTimeout(val);
-    invoke("set_silence_timeout", { ms: val });
+    invoke("set_silence_timeout", { ms: val }).catch((err) => setTuningError(String(err)));
   };
 
   const handleToggleAutostart = async (e: React.ChangeEvent<HTMLInputElement>) => {
@@ -85,9 +524,42 @@ function App() {
       setSilenceTimeout(settings.silence_timeout);
       setAllowCommands(settings.allow_commands);
       setDisablePunctuation(settings.disable_punctuation);
+      setSpokenPunctuation(settings.spoken_punctuation);
+      setItn(settings.itn);
+      setDisfluency(settings.disfluency);
+      setFillersText(settings.disfluency.fillers.join(", "));
       setShortcuts(settings.shortcuts);
+      setInputDevice(settings.input_device);
+      setChannelMix(settings.channel_mix);
+      if (settings.channel_mix.mode === "weighted") {
+        setChannelWeights(settings.channel_mix.weights.join(", "));
+      }
+      setResampleQuality(settings.resample_quality);
+      setPreferNativeRate(settings.prefer_native_rate);
+      setVadBackend(settings.vad_backend);
+      setAdaptiveVad(settings.adaptive_vad);
+      setVadThresholds(settings.vad_thresholds);
+      setVadTuning(settings.vad_tuning);
+      setModel(settings.model);
+      setLanguage(settings.language);
+      setTranslate(settings.translate);
+      setGlossary(settings.glossary);
+      setCarryContext(settings.carry_context);
+      setContextIdleReset(settings.context_idle_reset_ms);
+      setConfidenceFilter(settings.confidence_filter);
+      setFilterRules(settings.filter_rules);
+      setHttpBackend(settings.http_backend);
+      setDecodingPreset(settings.decoding_preset);
+      setDecoding(settings.decoding);
+      setMaxUtterance(settings.max_utterance_ms);
+      setActivationMode(settings.activation_mode);
+      setActivationHotkey(settings.activation_hotkey);
     });
 
+    invoke<string[]>("list_input_devices")
+      .then(setInputDevices)
+      .catch((err) => console.error("Device enumeration error:", err));
+
     // Check initial autostart status
     isEnabled().then(setAutostart);
 
@@ -96,14 +568,63 @@ function App() {
       setVadState(event.payload.state);
     });
 
+    // Listen for noise floor / threshold updates
+    const unlistenThresholds = listen<VadThresholdsPayload>("vad-thresholds", (event) => {
+      setVadThresholds(event.payload);
+    });
+
+    const unlistenCalibration = listen<VadCalibrationPayload>("vad-calibration", (event) => {
+      setCalibrating(event.payload.status === "started");
+      if (event.payload.thresholds) setVadThresholds(event.payload.thresholds);
+    });
+
+    // Listen for the microphone dropping out and coming back
+    const unlistenDevice = listen<AudioDevicePayload>("audio-device", (event) => {
+      setAudioDevice(event.payload);
+    });
+
+    // Listen for audio the capture loop couldn't keep up with
+    const unlistenMetrics = listen<AudioMetricsPayload>("audio-metrics", (event) => {
+      setDroppedSamples(event.payload.dropped_samples);
+    });
+
+    const unlistenEngineReady = listen<EngineStatusPayload>("engine-ready", (event) => setEngineStatus(event.payload));
+    const unlistenEngineError = listen<EngineStatusPayload>("engine-error", (event) => setEngineStatus(event.payload));
+    // The engine may have finished loading before the listeners were registered
+    Promise.all([unlistenEngineReady, unlistenEngineError]).then(() =>
+      invoke<EngineStatusPayload>("get_engine_status").then(setEngineStatus)
+    );
+
+    const unlistenModel = listen<ModelStatusPayload>("model-status", (event) => {
+      setModelStatus(event.payload);
+      // English-only models get swapped for multilingual ones by the backend
+      if (event.payload.status !== "error") setModel(event.payload.model);
+    });
+
+    // Interim text while speaking. Replaced by the final transcription.
+    const unlistenPartial = listen<TranscriptionPayload>("partial-transcription", (event) => {
+      setPartialText(event.payload.text);
+    });
+
     // Listen for Transcription updates
     const unlistenTrans = listen<TranscriptionPayload>("transcription", (event) => {
+      setPartialText("");
       setLastText(event.payload.text);
+      setDetectedLanguage(event.payload.language);
+      setLastConfidence(event.payload.confidence);
       setHistory((prev) => [event.payload.text, ...prev.slice(0, 9)]);
     });
 
     return () => {
       unlistenVad.then((fn) => fn());
+      unlistenThresholds.then((fn) => fn());
+      unlistenCalibration.then((fn) => fn());
+      unlistenDevice.then((fn) => fn());
+      unlistenMetrics.then((fn) => fn());
+      unlistenEngineReady.then((fn) => fn());
+      unlistenEngineError.then((fn) => fn());
+      unlistenModel.then((fn) => fn());
+      unlistenPartial.then((fn) => fn());
       unlistenTrans.then((fn) => fn());
     };
   }, []);
@@ -111,7 +632,19 @@ function App() {
   return (
     <div className="container">
       <div className={`status-bar ${vadState}`}>
-        <span>{vadState === "speaking" ? "🗣️ LISTENING" : "🤫 IDLE"}</span>
+        <span>
+          {vadState === "speaking" ? "🗣️ LISTENING" : "🤫 IDLE"}
+          {audioDevice?.status === "reconnecting" && (
+            <b className="audio-alert" title={`Waiting for ${audioDevice.device ?? "the default microphone"}`}>
+              🔌 RECONNECTING
+            </b>
+          )}
+          {droppedSamples > 0 && (
+            <b className="audio-alert" title="Samples lost to ring-buffer overruns since the microphone connected">
+              ⚠️ {droppedSamples} DROPPED
+            </b>
+          )}
+        </span>
         <div className="controls">
           <div className="slider-group" title="Silence timeout (ms)">
             <span>⏳ {silenceTimeout}ms</span>
@@ -136,10 +669,393 @@ function App() {
               <button className="close-btn" onClick={() => setSettingsOpen(false)}>×</button>
             </div>
 
+            <div className="setting-item">
+              <label>
+                Microphone
+                <select value={inputDevice ?? ""} onChange={handleInputDeviceChange}>
+                  <option value="">System Default</option>
+                  {inputDevices.map((name) => (
+                    <option key={name} value={name}>{name}</option>
+                  ))}
+                </select>
+              </label>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Channels
+                <select value={channelMix.mode} onChange={handleChannelModeChange}>
+                  <option value="average">Average</option>
+                  <option value="single">Single Channel</option>
+                  <option value="weighted">Weighted Mix</option>
+                </select>
+              </label>
+              {channelMix.mode === "single" && (
+                <label>
+                  Channel
+                  <input
+                    type="number"
+                    min="1"
+                    max="32"
+                    value={channelMix.channel + 1}
+                    onChange={(e) => applyChannelMix({ mode: "single", channel: Math.max(0, (parseInt(e.target.value) || 1) - 1) })}
+                  />
+                </label>
+              )}
+              {channelMix.mode === "weighted" && (
+                <label>
+                  Weights
+                  <input
+                    type="text"
+                    value={channelWeights}
+                    onChange={(e) => setChannelWeights(e.target.value)}
+                    onBlur={handleChannelWeightsCommit}
+                    onKeyDown={(e) => e.key === "Enter" && handleChannelWeightsCommit()}
+                  />
+                </label>
+              )}
+              <span className="tooltip">Applies to multi-channel microphones. Dead channels are left out automatically.</span>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Whisper Model
+                <select value={model} onChange={handleModelChange}>
+                  {WHISPER_MODELS.map((name) => (
+                    <option key={name} value={name}>{name}</option>
+                  ))}
+                </select>
+              </label>
+              {modelStatus && modelStatus.status !== "ready" && (
+                <span className="tooltip">
+                  {modelStatus.status === "loading"
+                    ? `Loading ${modelStatus.model}... the current model keeps working meanwhile.`
+                    : `Couldn't load ${modelStatus.model}: ${modelStatus.error}`}
+                </span>
+              )}
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Decoding
+                <select value={decodingPreset} onChange={handleDecodingPresetChange}>
+                  <option value="fastest">Fastest</option>
+                  <option value="balanced">Balanced</option>
+                  <option value="accurate">Accurate</option>
+                  <option value="custom">Custom</option>
+                </select>
+              </label>
+              {decoding && (
+                <>
+                  <label>
+                    Sampling
+                    <select
+                      value={decoding.sampling}
+                      onChange={(e) => handleDecodingChange({ sampling: e.target.value as DecodingParams["sampling"] })}
+                    >
+                      <option value="greedy">Greedy</option>
+                      <option value="beam_search">Beam Search</option>
+                    </select>
+                  </label>
+                  {decoding.sampling === "beam_search" && (
+                    <label>
+                      Beam Size
+                      <input
+                        type="number"
+                        min="1"
+                        max="8"
+                        value={decoding.beam_size}
+                        onChange={(e) => handleDecodingChange({ beam_size: parseInt(e.target.value) })}
+                      />
+                    </label>
+                  )}
+                  <label>
+                    Threads (0 = auto)
+                    <input
+                      type="number"
+                      min="0"
+                      max="64"
+                      value={decoding.threads}
+                      onChange={(e) => handleDecodingChange({ threads: parseInt(e.target.value) })}
+                    />
+                  </label>
+                  <label>
+                    Temperature
+                    <input
+                      type="number"
+                      min="0"
+                      max="1"
+                      step="0.1"
+                      value={decoding.temperature}
+                      onChange={(e) => handleDecodingChange({ temperature: parseFloat(e.target.value) })}
+                    />
+                  </label>
+                  <label>
+                    Fallback Step (0 = off)
+                    <input
+                      type="number"
+                      min="0"
+                      max="1"
+                      step="0.1"
+                      value={decoding.temperature_increment}
+                      onChange={(e) => handleDecodingChange({ temperature_increment: parseFloat(e.target.value) })}
+                    />
+                  </label>
+                  <label>
+                    Max Tokens (0 = unlimited)
+                    <input
+                      type="number"
+                      min="0"
+                      max="224"
+                      value={decoding.max_tokens}
+                      onChange={(e) => handleDecodingChange({ max_tokens: parseInt(e.target.value) })}
+                    />
+                  </label>
+                  <label>
+                    <input
+                      type="checkbox"
+                      checked={decoding.suppress_blank}
+                      onChange={(e) => handleDecodingChange({ suppress_blank: e.target.checked })}
+                    />
+                    Suppress Blank
+                  </label>
+                  <label>
+                    <input
+                      type="checkbox"
+                      checked={decoding.no_context}
+                      onChange={(e) => handleDecodingChange({ no_context: e.target.checked })}
+                    />
+                    No Context
+                  </label>
+                  <label>
+                    <input
+                      type="checkbox"
+                      checked={decoding.single_segment}
+                      onChange={(e) => handleDecodingChange({ single_segment: e.target.checked })}
+                    />
+                    Single Segment
+                  </label>
+                </>
+              )}
+              {decodingError && <span className="tooltip">{decodingError}</span>}
+            </div>
+
+            <div className="setting-item">
+              <label>
+                <input
+                  type="checkbox"
+                  checked={httpBackend.enabled}
+                  onChange={(e) => handleHttpBackendChange({ enabled: e.target.checked })}
+                />
+                Transcription Server
+              </label>
+              {httpBackend.enabled && (
+                <>
+                  <label>
+                    URL
+                    <input
+                      type="text"
+                      value={httpBackend.url}
+                      onChange={(e) => setHttpBackend({ ...httpBackend, url: e.target.value })}
+                      onBlur={() => handleHttpBackendChange({})}
+                      onKeyDown={(e) => e.key === "Enter" && handleHttpBackendChange({})}
+                    />
+                  </label>
+                  <label>
+                    Model
+                    <input
+                      type="text"
+                      value={httpBackend.model}
+                      onChange={(e) => setHttpBackend({ ...httpBackend, model: e.target.value })}
+                      onBlur={() => handleHttpBackendChange({})}
+                      onKeyDown={(e) => e.key === "Enter" && handleHttpBackendChange({})}
+                    />
+                  </label>
+                  <label>
+                    Timeout
+                    <select
+                      value={httpBackend.timeout_ms}
+                      onChange={(e) => handleHttpBackendChange({ timeout_ms: parseInt(e.target.value) })}
+                    >
+                      <option value={2000}>2s</option>
+                      <option value={5000}>5s</option>
+                      <option value={10000}>10s</option>
+                      <option value={30000}>30s</option>
+                    </select>
+                  </label>
+                </>
+              )}
+              <span className="tooltip">
+                {httpBackendError ??
+                  "An OpenAI-compatible server (faster-whisper, whisper.cpp) on your network. The local model takes over when it can't be reached."}
+              </span>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Language
+                <select value={language} onChange={handleLanguageChange}>
+                  {LANGUAGES.map(([code, name]) => (
+                    <option key={code} value={code}>{name}</option>
+                  ))}
+                </select>
+              </label>
+              <label>
+                <input type="checkbox" checked={translate} onChange={handleToggleTranslate} />
+                Translate to English
+              </label>
+              {(language !== "en" || translate) && (model.endsWith(".en") || model.startsWith("distil-")) && (
+                <span className="tooltip">English-only models are switched to their multilingual version.</span>
+              )}
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Activation
+                <select value={activationMode} onChange={handleActivationModeChange}>
+                  <option value="vad">Voice Detection</option>
+                  <option value="push_to_talk">Push-to-Talk</option>
+                  <option value="toggle">Toggle</option>
+                </select>
+              </label>
+              {activationMode !== "vad" && (
+                <label>
+                  Hotkey
+                  <input
+                    type="text"
+                    value={activationHotkey}
+                    onChange={(e) => setActivationHotkey(e.target.value)}
+                    onBlur={handleHotkeyCommit}
+                    onKeyDown={(e) => e.key === "Enter" && handleHotkeyCommit()}
+                  />
+                </label>
+              )}
+              {hotkeyError && <span className="tooltip">{hotkeyError}</span>}
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Voice Detection
+                <select value={vadBackend} onChange={handleVadBackendChange}>
+                  <option value="energy">Energy</option>
+                  <option value="spectral">Spectral</option>
+                  <option value="silero">Silero (Neural)</option>
+                </select>
+              </label>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                <input type="checkbox" checked={adaptiveVad} onChange={handleToggleAdaptiveVad} />
+                Adapt to Background Noise
+              </label>
+              <button className="shortcut-mgr-btn" onClick={handleCalibrate} disabled={calibrating}>
+                {calibrating ? "Calibrating... stay quiet" : "Calibrate Noise Floor"}
+              </button>
+              {vadThresholds && (
+                <span className="tooltip">
+                  Floor {vadThresholds.noise_floor.toFixed(4)} · Start {vadThresholds.start_threshold.toFixed(4)} · Stop {vadThresholds.stop_threshold.toFixed(4)}
+                </span>
+              )}
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Start Threshold
+                <input
+                  type="number"
+                  min="0.0005"
+                  max="0.5"
+                  step="0.0005"
+                  value={vadThresholds?.start_threshold ?? 0}
+                  onChange={(e) => handleThresholdChange("start_threshold", parseFloat(e.target.value))}
+                />
+              </label>
+              <label>
+                Stop Threshold
+                <input
+                  type="number"
+                  min="0.0001"
+                  max="0.5"
+                  step="0.0005"
+                  value={vadThresholds?.stop_threshold ?? 0}
+                  onChange={(e) => handleThresholdChange("stop_threshold", parseFloat(e.target.value))}
+                />
+              </label>
+              <label>
+                Start Window (ms)
+                <input
+                  type="number"
+                  min="30"
+                  max="2000"
+                  step="30"
+                  value={vadTuning.start_window_ms}
+                  onChange={(e) => handleTuningChange("start_window_ms", parseInt(e.target.value))}
+                />
+              </label>
+              <label>
+                Pre-roll (ms)
+                <input
+                  type="number"
+                  min="0"
+                  max="2000"
+                  step="50"
+                  value={vadTuning.pre_roll_ms}
+                  onChange={(e) => handleTuningChange("pre_roll_ms", parseInt(e.target.value))}
+                />
+              </label>
+              <label>
+                Post-roll (ms)
+                <input
+                  type="number"
+                  min="0"
+                  max="1000"
+                  step="50"
+                  value={vadTuning.post_roll_ms}
+                  onChange={(e) => handleTuningChange("post_roll_ms", parseInt(e.target.value))}
+                />
+              </label>
+              <button className="shortcut-mgr-btn" onClick={handleResetTuning}>
+                Reset to Defaults
+              </button>
+              {tuningError && <span className="tooltip">{tuningError}</span>}
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Max Utterance Length
+                <select value={maxUtterance} onChange={handleMaxUtteranceChange}>
+                  <option value={0}>Unlimited</option>
+                  <option value={10000}>10s</option>
+                  <option value={15000}>15s</option>
+                  <option value={20000}>20s</option>
+                  <option value={30000}>30s</option>
+                </select>
+              </label>
+              <span className="tooltip">Long dictation is split at a natural pause and typed while you keep talking.</span>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Resampling
+                <select value={resampleQuality} onChange={handleResampleQualityChange}>
+                  <option value="fast">Fast</option>
+                  <option value="high">High Quality</option>
+                </select>
+              </label>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                <input type="checkbox" checked={preferNativeRate} onChange={handleToggleNativeRate} />
+                Capture at 16kHz when supported
+              </label>
+            </div>
+
             <div className="setting-item">
               <label>
                 <input type="checkbox" checked={autoSpace} onChange={handleToggleSpace} />
-                Auto-Space after sentence
+                Auto-Space between sentences
               </label>
             </div>
 
@@ -150,6 +1066,55 @@ function App() {
               </label>
             </div>
 
+            <div className="setting-item">
+              <label>
+                <input type="checkbox" checked={spokenPunctuation} onChange={handleToggleSpokenPunctuation} />
+                Spoken Punctuation
+              </label>
+              <span className="tooltip">Say "comma", "period", "question mark", "colon", "open quote" / "close quote", "new line" or "new paragraph" mid-sentence. Works with Disable Punctuation on.</span>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                Filler Words
+                <select
+                  value={disfluency.mode}
+                  onChange={(e) => handleDisfluencyChange({ mode: e.target.value as DisfluencyMode })}
+                >
+                  <option value="off">Keep</option>
+                  <option value="conservative">Remove fillers</option>
+                  <option value="aggressive">Remove fillers, repeats & corrections</option>
+                </select>
+              </label>
+              {disfluency.mode !== "off" && (
+                <label>
+                  Fillers
+                  <input
+                    type="text"
+                    value={fillersText}
+                    onChange={(e) => setFillersText(e.target.value)}
+                    onBlur={commitFillers}
+                    onKeyDown={(e) => e.key === "Enter" && commitFillers()}
+                  />
+                </label>
+              )}
+              <span className="tooltip">
+                {disfluencyError ??
+                  "Comma-separated. Phrases like \"you know\" are only removed when set off by commas. The aggressive mode also turns \"the the\" into \"the\" and \"at five, no, six\" into \"at six\"."}
+              </span>
+            </div>
+
+            <div className="setting-item">
+              <label>Write Out Spoken Forms</label>
+              {ITN_CATEGORIES.map(({ key, label }) => (
+                <label key={key}>
+                  <input type="checkbox" checked={itn[key]} onChange={(e) => handleItnChange({ [key]: e.target.checked })} />
+                  {label}
+                </label>
+              ))}
+              <span className="tooltip">Turns "twenty three dollars at five thirty pm" into "$23 at 5:30 PM". English transcripts only.</span>
+            </div>
+
             <div className="setting-item">
               <label>
                 <input type="checkbox" checked={autostart} onChange={handleToggleAutostart} />
@@ -164,6 +1129,74 @@ function App() {
               </label>
               <button className="shortcut-mgr-btn" onClick={() => setShortcutsOpen(true)}>Manage Shortcuts ({Object.keys(shortcuts).length})</button>
             </div>
+
+            <div className="setting-item">
+              <label>Custom Vocabulary</label>
+              <button className="shortcut-mgr-btn" onClick={() => setGlossaryOpen(true)}>Manage Glossary ({glossary.length})</button>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                <input
+                  type="checkbox"
+                  checked={confidenceFilter.enabled}
+                  onChange={(e) => handleConfidenceFilterChange({ enabled: e.target.checked })}
+                />
+                Reject low-confidence text
+              </label>
+              {confidenceFilter.enabled && (
+                <>
+                  <label>
+                    Min confidence {Math.round(confidenceFilter.min_probability * 100)}%
+                    <input
+                      type="range"
+                      min="0"
+                      max="1"
+                      step="0.05"
+                      value={confidenceFilter.min_probability}
+                      onChange={(e) => handleConfidenceFilterChange({ min_probability: parseFloat(e.target.value) })}
+                    />
+                  </label>
+                  <label>
+                    Max no-speech {Math.round(confidenceFilter.max_no_speech_probability * 100)}%
+                    <input
+                      type="range"
+                      min="0"
+                      max="1"
+                      step="0.05"
+                      value={confidenceFilter.max_no_speech_probability}
+                      onChange={(e) => handleConfidenceFilterChange({ max_no_speech_probability: parseFloat(e.target.value) })}
+                    />
+                  </label>
+                </>
+              )}
+              <span className="tooltip">Drops phantom phrases like "Thank you for watching." that Whisper invents from coughs and noise.</span>
+            </div>
+
+            <div className="setting-item">
+              <label>Noise Filter</label>
+              <button className="shortcut-mgr-btn" onClick={() => setFilterRulesOpen(true)}>Manage Rules ({filterRules.length})</button>
+              <span className="tooltip">Drop, strip or replace text such as [BLANK_AUDIO] or phrases Whisper keeps inventing.</span>
+            </div>
+
+            <div className="setting-item">
+              <label>
+                <input type="checkbox" checked={carryContext} onChange={handleToggleCarryContext} />
+                Continue from previous sentence
+              </label>
+              {carryContext && (
+                <label>
+                  Forget after
+                  <select value={contextIdleReset} onChange={handleContextIdleResetChange}>
+                    <option value={10000}>10s</option>
+                    <option value={30000}>30s</option>
+                    <option value={60000}>1 min</option>
+                    <option value={300000}>5 min</option>
+                  </select>
+                </label>
+              )}
+              <span className="tooltip">Gives Whisper the end of your last transcript for consistent casing, punctuation and names. Resets when you switch windows.</span>
+            </div>
           </div>
         </div>
       )}
@@ -176,10 +1209,47 @@ function App() {
         />
       )}
 
-      {!settingsOpen && !shortcutsOpen && (
+      {glossaryOpen && (
+        <GlossaryModal
+          glossary={glossary}
+          onClose={() => setGlossaryOpen(false)}
+          onUpdate={setGlossary}
+        />
+      )}
+
+      {filterRulesOpen && (
+        <FilterRulesModal
+          rules={filterRules}
+          onClose={() => setFilterRulesOpen(false)}
+          onUpdate={setFilterRules}
+        />
+      )}
+
+      {!settingsOpen && !shortcutsOpen && !glossaryOpen && !filterRulesOpen && (
         <>
           <div className="main-display">
-            <h1>{lastText || "Start speaking..."}</h1>
+            {engineStatus.status === "loading" ? (
+              <h1 className="partial">Loading model...</h1>
+            ) : engineStatus.status === "error" ? (
+              <h1 className="partial">Couldn't start transcription: {engineStatus.error}</h1>
+            ) : partialText ? (
+              <h1 className="partial">{partialText}</h1>
+            ) : (
+              <h1>
+                {detectedLanguage && <span className="language-tag">{detectedLanguage}</span>}
+                {lastConfidence && lastConfidence.probability !== null && lastText && (
+                  <span
+                    className="language-tag"
+                    title={`Confidence per segment: ${lastConfidence.segments
+                      .map((s) => (s.probability === null ? "unscored" : `${Math.round(s.probability * 100)}%`))
+                      .join(", ")}`}
+                  >
+                    {Math.round(lastConfidence.probability * 100)}%
+                  </span>
+                )}
+                {lastText || "Start speaking..."}
+              </h1>
+            )}
           </div>
 
           <div className="history">
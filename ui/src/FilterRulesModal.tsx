@@ -0,0 +1,122 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+export type MatchKind = "exact" | "contains" | "regex";
+export type FilterAction = "drop" | "strip" | "replace";
+
+export interface FilterRule {
+    kind: MatchKind;
+    pattern: string;
+    action: FilterAction;
+    replacement: string;
+}
+
+interface FilterRulesModalProps {
+    rules: FilterRule[];
+    onClose: () => void;
+    onUpdate: (rules: FilterRule[]) => void;
+}
+
+const KIND_LABELS: Record<MatchKind, string> = {
+    exact: "Whole text is",
+    contains: "Text contains",
+    regex: "Regex",
+};
+
+export function FilterRulesModal({ rules, onClose, onUpdate }: FilterRulesModalProps) {
+    const [kind, setKind] = useState<MatchKind>("exact");
+    const [pattern, setPattern] = useState("");
+    const [action, setAction] = useState<FilterAction>("drop");
+    const [replacement, setReplacement] = useState("");
+    const [error, setError] = useState<string | null>(null);
+
+    const save = async (next: FilterRule[]) => {
+        try {
+            await invoke("set_filter_rules", { rules: next });
+            onUpdate(next);
+            setError(null);
+            return true;
+        } catch (e) {
+            setError(String(e));
+            return false;
+        }
+    };
+
+    const handleAdd = async () => {
+        if (!pattern.trim()) return;
+        const rule: FilterRule = { kind, pattern, action, replacement: action === "replace" ? replacement : "" };
+        if (await save([...rules, rule])) {
+            setPattern("");
+            setReplacement("");
+        }
+    };
+
+    const handleReset = async () => {
+        const defaults = await invoke<FilterRule[]>("reset_filter_rules");
+        onUpdate(defaults);
+        setError(null);
+    };
+
+    return (
+        <div className="settings-overlay">
+            <div className="settings-modal shortcuts-modal">
+                <div className="settings-header">
+                    <h3>Noise Filter</h3>
+                    <button className="close-btn" onClick={onClose}>×</button>
+                </div>
+
+                <div className="shortcut-form">
+                    <select value={kind} onChange={(e) => setKind(e.target.value as MatchKind)}>
+                        <option value="exact">{KIND_LABELS.exact}</option>
+                        <option value="contains">{KIND_LABELS.contains}</option>
+                        <option value="regex">{KIND_LABELS.regex}</option>
+                    </select>
+                    <input
+                        type="text"
+                        placeholder={kind === "regex" ? "\\[.*?\\]" : "Thanks for watching"}
+                        value={pattern}
+                        onChange={(e) => setPattern(e.target.value)}
+                    />
+                    <select value={action} onChange={(e) => setAction(e.target.value as FilterAction)}>
+                        <option value="drop">Drop</option>
+                        <option value="strip">Strip</option>
+                        <option value="replace">Replace</option>
+                    </select>
+                    {action === "replace" && (
+                        <input
+                            type="text"
+                            placeholder="With..."
+                            value={replacement}
+                            onChange={(e) => setReplacement(e.target.value)}
+                        />
+                    )}
+                    <button className="add-btn" onClick={handleAdd}>Add</button>
+                </div>
+
+                <div className="shortcut-tokens">
+                    {error ? (
+                        <span className="tooltip">{error}</span>
+                    ) : (
+                        <span>Rules run top to bottom. Drop discards the whole utterance, Strip removes the match.</span>
+                    )}
+                    <button className="shortcut-mgr-btn" onClick={handleReset}>Reset to defaults</button>
+                </div>
+
+                <div className="shortcut-list">
+                    {rules.map((rule, i) => (
+                        <div key={`${i}-${rule.pattern}`} className="shortcut-item">
+                            <div className="shortcut-info">
+                                <span className="key">{KIND_LABELS[rule.kind]} <code>{rule.pattern}</code></span>
+                                <span className="arrow">→</span>
+                                <span className="value">
+                                    {rule.action === "replace" ? `"${rule.replacement}"` : rule.action}
+                                </span>
+                            </div>
+                            <button className="delete-btn" onClick={() => save(rules.filter((_, j) => j !== i))}>×</button>
+                        </div>
+                    ))}
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,72 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+export interface GlossaryTerm {
+    term: string;
+    last_used: number;
+}
+
+interface GlossaryModalProps {
+    glossary: GlossaryTerm[];
+    onClose: () => void;
+    onUpdate: (glossary: GlossaryTerm[]) => void;
+}
+
+export function GlossaryModal({ glossary, onClose, onUpdate }: GlossaryModalProps) {
+    const [newTerm, setNewTerm] = useState("");
+
+    const handleAdd = async () => {
+        const term = newTerm.trim();
+        if (!term) return;
+        await invoke("upsert_glossary_term", { term });
+        const now = Math.floor(Date.now() / 1000);
+        const rest = glossary.filter((t) => t.term.toLowerCase() !== term.toLowerCase());
+        onUpdate([{ term, last_used: now }, ...rest]);
+        setNewTerm("");
+    };
+
+    const handleDelete = async (term: string) => {
+        await invoke("delete_glossary_term", { term });
+        onUpdate(glossary.filter((t) => t.term !== term));
+    };
+
+    // Most recently used first, the order the prompt is filled in
+    const sorted = [...glossary].sort((a, b) => b.last_used - a.last_used);
+
+    return (
+        <div className="settings-overlay">
+            <div className="settings-modal shortcuts-modal">
+                <div className="settings-header">
+                    <h3>Glossary</h3>
+                    <button className="close-btn" onClick={onClose}>×</button>
+                </div>
+
+                <div className="shortcut-form">
+                    <input
+                        type="text"
+                        placeholder="Name, product or acronym"
+                        value={newTerm}
+                        onChange={(e) => setNewTerm(e.target.value)}
+                        onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+                    />
+                    <button className="add-btn" onClick={handleAdd}>Add</button>
+                </div>
+
+                <div className="shortcut-tokens">
+                    <span>Recently used terms are preferred when the list is too long for the model's prompt.</span>
+                </div>
+
+                <div className="shortcut-list">
+                    {sorted.map(({ term }) => (
+                        <div key={term} className="shortcut-item">
+                            <div className="shortcut-info">
+                                <span className="key">{term}</span>
+                            </div>
+                            <button className="delete-btn" onClick={() => handleDelete(term)}>×</button>
+                        </div>
+                    ))}
+                </div>
+            </div>
+        </div>
+    );
+}